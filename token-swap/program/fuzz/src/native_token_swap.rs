@@ -622,6 +622,7 @@ impl NativeTokenSwap {
                 pool_token_amount,
                 minimum_token_a_amount: 0,
                 minimum_token_b_amount: 0,
+                unwrap_sol: false,
             };
             self.withdraw_all_token_types(
                 pool_account,
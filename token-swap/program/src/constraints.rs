@@ -13,6 +13,76 @@ use {
     solana_program::program_error::ProgramError,
 };
 
+/// Bounds on the initial price of token A, quoted in token B base units per
+/// token A base unit, each given as a fraction.
+pub struct PriceRange {
+    /// Numerator of the lowest allowed price
+    pub min_numerator: u64,
+    /// Denominator of the lowest allowed price
+    pub min_denominator: u64,
+    /// Numerator of the highest allowed price
+    pub max_numerator: u64,
+    /// Denominator of the highest allowed price
+    pub max_denominator: u64,
+}
+
+/// Inclusive bounds on each component of the fees a pool may be created
+/// with, numerators and denominators alike.
+pub struct FeeRange {
+    /// Lowest allowed value of each fee component
+    pub min: Fees,
+    /// Highest allowed value of each fee component
+    pub max: Fees,
+}
+
+impl FeeRange {
+    /// Checks that every component of `fees` is within the bounds
+    pub fn contains(&self, fees: &Fees) -> bool {
+        let within = |value: u64, min: u64, max: u64| min <= value && value <= max;
+        within(
+            fees.trade_fee_numerator,
+            self.min.trade_fee_numerator,
+            self.max.trade_fee_numerator,
+        ) && within(
+            fees.trade_fee_denominator,
+            self.min.trade_fee_denominator,
+            self.max.trade_fee_denominator,
+        ) && within(
+            fees.owner_trade_fee_numerator,
+            self.min.owner_trade_fee_numerator,
+            self.max.owner_trade_fee_numerator,
+        ) && within(
+            fees.owner_trade_fee_denominator,
+            self.min.owner_trade_fee_denominator,
+            self.max.owner_trade_fee_denominator,
+        ) && within(
+            fees.owner_withdraw_fee_numerator,
+            self.min.owner_withdraw_fee_numerator,
+            self.max.owner_withdraw_fee_numerator,
+        ) && within(
+            fees.owner_withdraw_fee_denominator,
+            self.min.owner_withdraw_fee_denominator,
+            self.max.owner_withdraw_fee_denominator,
+        ) && within(
+            fees.host_fee_numerator,
+            self.min.host_fee_numerator,
+            self.max.host_fee_numerator,
+        ) && within(
+            fees.host_fee_denominator,
+            self.min.host_fee_denominator,
+            self.max.host_fee_denominator,
+        )
+    }
+}
+
+/// One-time fee, in lamports, for creating a pool.
+pub struct CreationFee<'a> {
+    /// Lamports the initializer pays
+    pub creation_fee_lamports: u64,
+    /// System account receiving the fee
+    pub treasury: &'a str,
+}
+
 /// Encodes fee constraints, used in multihost environments where the program
 /// may be used by multiple frontends, to ensure that proper fees are being
 /// assessed.
@@ -31,6 +101,27 @@ pub struct SwapConstraints<'a> {
     pub valid_curve_types: &'a [CurveType],
     /// Valid fees
     pub fees: &'a Fees,
+    /// Minimum number of decimals for the pool token mint. Pool tokens with
+    /// too few decimals round away most of the LP share and fee precision.
+    pub min_pool_mint_decimals: u8,
+    /// Allowed price implied by the initial reserves, if restricted. Keeps
+    /// pools from being seeded at a manipulated price.
+    pub initial_price_range: Option<PriceRange>,
+    /// Whether token-2022 reserve and fee accounts must have the
+    /// `ImmutableOwner` extension, so their owner can never be reassigned.
+    /// Accounts of the original token program cannot carry extensions and
+    /// are not checked.
+    pub require_immutable_owner: bool,
+    /// Whether the pool fee account may be owned by the swap authority, so
+    /// that owner fees stay under the program's control. Rejected by default
+    /// to avoid fees being captured by the pool itself.
+    pub allow_authority_owned_fee_account: bool,
+    /// Allowed range of each fee component, if fees may vary between pools.
+    /// Replaces the check against `fees` when set.
+    pub fee_range: Option<FeeRange>,
+    /// Fee charged to whoever creates a pool, if any. Launchpads use it to
+    /// charge for listing.
+    pub creation_fee: Option<CreationFee<'a>>,
 }
 
 impl<'a> SwapConstraints<'a> {
@@ -54,6 +145,14 @@ impl<'a> SwapConstraints<'a> {
 
     /// Checks that the provided curve is valid for the given constraints
     pub fn validate_fees(&self, fees: &Fees) -> Result<(), ProgramError> {
+        // 配置了费率区间时，每一项费率参数只需落在 [min, max] 内
+        if let Some(range) = &self.fee_range {
+            return if range.contains(fees) {
+                Ok(())
+            } else {
+                Err(SwapError::InvalidFee.into())
+            };
+        }
         if fees.trade_fee_numerator >= self.fees.trade_fee_numerator
             && fees.trade_fee_denominator == self.fees.trade_fee_denominator
             && fees.owner_trade_fee_numerator >= self.fees.owner_trade_fee_numerator
@@ -68,6 +167,48 @@ impl<'a> SwapConstraints<'a> {
             Err(SwapError::InvalidFee.into())
         }
     }
+
+    /// Checks that the pool token mint has enough decimals
+    pub fn validate_pool_mint_decimals(&self, decimals: u8) -> Result<(), ProgramError> {
+        if decimals >= self.min_pool_mint_decimals {
+            Ok(())
+        } else {
+            Err(SwapError::InvalidPoolMintDecimals.into())
+        }
+    }
+
+    /// Checks that the price implied by the initial reserves, token B per
+    /// token A, is within the allowed range, bounds included
+    pub fn validate_initial_price(
+        &self,
+        token_a_amount: u64,
+        token_b_amount: u64,
+    ) -> Result<(), ProgramError> {
+        let Some(range) = &self.initial_price_range else {
+            return Ok(());
+        };
+        let token_a_amount = u128::from(token_a_amount);
+        let token_b_amount = u128::from(token_b_amount);
+        // b / a >= min_n / min_d and b / a <= max_n / max_d, cross multiplied
+        if token_b_amount * u128::from(range.min_denominator)
+            >= u128::from(range.min_numerator) * token_a_amount
+            && token_b_amount * u128::from(range.max_denominator)
+                <= u128::from(range.max_numerator) * token_a_amount
+        {
+            Ok(())
+        } else {
+            Err(SwapError::PriceOutOfRange.into())
+        }
+    }
+
+    /// Checks that a token account has an immutable owner, if required
+    pub fn validate_immutable_owner(&self, has_immutable_owner: bool) -> Result<(), ProgramError> {
+        if !self.require_immutable_owner || has_immutable_owner {
+            Ok(())
+        } else {
+            Err(SwapError::MutableAccountOwner.into())
+        }
+    }
 }
 
 #[cfg(feature = "production")]
@@ -85,6 +226,8 @@ const FEES: &Fees = &Fees {
 };
 #[cfg(feature = "production")]
 const VALID_CURVE_TYPES: &[CurveType] = &[CurveType::ConstantPrice, CurveType::ConstantProduct];
+#[cfg(feature = "production")]
+const MIN_POOL_MINT_DECIMALS: u8 = 6;
 
 /// Fee structure defined by program creator in order to enforce certain
 /// fees when others use the program.  Adds checks on pool creation and
@@ -99,6 +242,12 @@ pub const SWAP_CONSTRAINTS: Option<SwapConstraints> = {
             owner_key: OWNER_KEY,
             valid_curve_types: VALID_CURVE_TYPES,
             fees: FEES,
+            min_pool_mint_decimals: MIN_POOL_MINT_DECIMALS,
+            initial_price_range: None,
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            fee_range: None,
+            creation_fee: None,
         })
     }
     #[cfg(not(feature = "production"))]
@@ -146,6 +295,12 @@ mod tests {
             owner_key,
             valid_curve_types: &[curve_type],
             fees: &valid_fees,
+            min_pool_mint_decimals: 0,
+            initial_price_range: None,
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            fee_range: None,
+            creation_fee: None,
         };
 
         constraints.validate_curve(&swap_curve).unwrap();
@@ -206,4 +361,194 @@ mod tests {
             constraints.validate_curve(&swap_curve),
         );
     }
+
+    #[test]
+    fn validate_fees_in_range() {
+        let fees = Fees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator: 5,
+            owner_trade_fee_denominator: 10_000,
+            owner_withdraw_fee_numerator: 0,
+            owner_withdraw_fee_denominator: 0,
+            host_fee_numerator: 20,
+            host_fee_denominator: 100,
+        };
+        // trade fee between 0.1% and 0.5%, owner fee up to 0.1%
+        let constraints = SwapConstraints {
+            owner_key: None,
+            valid_curve_types: &[],
+            fees: &fees,
+            min_pool_mint_decimals: 0,
+            initial_price_range: None,
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            fee_range: Some(FeeRange {
+                min: Fees {
+                    trade_fee_numerator: 10,
+                    trade_fee_denominator: 10_000,
+                    owner_trade_fee_numerator: 0,
+                    owner_trade_fee_denominator: 10_000,
+                    owner_withdraw_fee_numerator: 0,
+                    owner_withdraw_fee_denominator: 0,
+                    host_fee_numerator: 0,
+                    host_fee_denominator: 100,
+                },
+                max: Fees {
+                    trade_fee_numerator: 50,
+                    trade_fee_denominator: 10_000,
+                    owner_trade_fee_numerator: 10,
+                    owner_trade_fee_denominator: 10_000,
+                    owner_withdraw_fee_numerator: 0,
+                    owner_withdraw_fee_denominator: 0,
+                    host_fee_numerator: 20,
+                    host_fee_denominator: 100,
+                },
+            }),
+            creation_fee: None,
+        };
+        constraints.validate_fees(&fees).unwrap();
+
+        // bounds are inclusive, and the exact fees no longer apply
+        for trade_fee_numerator in [10, 50] {
+            let fees = Fees {
+                trade_fee_numerator,
+                owner_trade_fee_numerator: 0,
+                ..fees.clone()
+            };
+            constraints.validate_fees(&fees).unwrap();
+        }
+
+        let out_of_range = [
+            Fees {
+                trade_fee_numerator: 9,
+                ..fees.clone()
+            },
+            Fees {
+                trade_fee_numerator: 51,
+                ..fees.clone()
+            },
+            Fees {
+                owner_trade_fee_numerator: 11,
+                ..fees.clone()
+            },
+            Fees {
+                owner_trade_fee_denominator: 1_000,
+                ..fees.clone()
+            },
+            Fees {
+                owner_withdraw_fee_numerator: 1,
+                owner_withdraw_fee_denominator: 100,
+                ..fees.clone()
+            },
+            Fees {
+                host_fee_numerator: 21,
+                ..fees.clone()
+            },
+        ];
+        for fees in out_of_range {
+            assert_eq!(
+                Err(SwapError::InvalidFee.into()),
+                constraints.validate_fees(&fees),
+            );
+        }
+    }
+
+    #[test]
+    fn validate_pool_mint_decimals() {
+        let fees = Fees::default();
+        let constraints = SwapConstraints {
+            owner_key: None,
+            valid_curve_types: &[],
+            fees: &fees,
+            min_pool_mint_decimals: 6,
+            initial_price_range: None,
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            fee_range: None,
+            creation_fee: None,
+        };
+        constraints.validate_pool_mint_decimals(6).unwrap();
+        constraints.validate_pool_mint_decimals(9).unwrap();
+        assert_eq!(
+            Err(SwapError::InvalidPoolMintDecimals.into()),
+            constraints.validate_pool_mint_decimals(5),
+        );
+        assert_eq!(
+            Err(SwapError::InvalidPoolMintDecimals.into()),
+            constraints.validate_pool_mint_decimals(0),
+        );
+    }
+
+    #[test]
+    fn validate_initial_price() {
+        let fees = Fees::default();
+        // between 2 and 5 / 2 token B per token A
+        let constraints = SwapConstraints {
+            owner_key: None,
+            valid_curve_types: &[],
+            fees: &fees,
+            min_pool_mint_decimals: 0,
+            initial_price_range: Some(PriceRange {
+                min_numerator: 2,
+                min_denominator: 1,
+                max_numerator: 5,
+                max_denominator: 2,
+            }),
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            fee_range: None,
+            creation_fee: None,
+        };
+        constraints.validate_initial_price(1_000, 2_000).unwrap();
+        constraints.validate_initial_price(1_000, 2_500).unwrap();
+        constraints.validate_initial_price(1_000, 2_200).unwrap();
+        assert_eq!(
+            Err(SwapError::PriceOutOfRange.into()),
+            constraints.validate_initial_price(1_000, 1_999),
+        );
+        assert_eq!(
+            Err(SwapError::PriceOutOfRange.into()),
+            constraints.validate_initial_price(1_000, 2_501),
+        );
+        assert_eq!(
+            Err(SwapError::PriceOutOfRange.into()),
+            constraints.validate_initial_price(u64::MAX, u64::MAX),
+        );
+
+        let constraints = SwapConstraints {
+            initial_price_range: None,
+            ..constraints
+        };
+        constraints.validate_initial_price(1, u64::MAX).unwrap();
+    }
+
+    #[test]
+    fn validate_immutable_owner() {
+        let fees = Fees::default();
+        let constraints = SwapConstraints {
+            owner_key: None,
+            valid_curve_types: &[],
+            fees: &fees,
+            min_pool_mint_decimals: 0,
+            initial_price_range: None,
+            require_immutable_owner: true,
+            allow_authority_owned_fee_account: false,
+            fee_range: None,
+            creation_fee: None,
+        };
+        constraints.validate_immutable_owner(true).unwrap();
+        assert_eq!(
+            Err(SwapError::MutableAccountOwner.into()),
+            constraints.validate_immutable_owner(false),
+        );
+
+        let constraints = SwapConstraints {
+            require_immutable_owner: false,
+            allow_authority_owned_fee_account: false,
+            ..constraints
+        };
+        constraints.validate_immutable_owner(true).unwrap();
+        constraints.validate_immutable_owner(false).unwrap();
+    }
 }
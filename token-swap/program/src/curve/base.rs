@@ -1,14 +1,22 @@
 //! Base curve implementation
 
+#[cfg(feature = "test-curve")]
+use crate::curve::identity::IdentityCurve;
 #[cfg(feature = "fuzz")]
 use arbitrary::Arbitrary;
 use {
-    crate::curve::{
-        calculator::{CurveCalculator, RoundDirection, SwapWithoutFeesResult, TradeDirection},
-        constant_price::ConstantPriceCurve,
-        constant_product::ConstantProductCurve,
-        fees::Fees,
-        offset::OffsetCurve,
+    crate::{
+        curve::{
+            calculator::{
+                CurveCalculator, RoundDirection, SwapWithoutFeesResult, TradeDirection,
+                TradingTokenResult,
+            },
+            constant_price::ConstantPriceCurve,
+            constant_product::ConstantProductCurve,
+            fees::Fees,
+            offset::OffsetCurve,
+        },
+        error::SwapError,
     },
     arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs},
     solana_program::{
@@ -40,6 +48,9 @@ pub enum CurveType {
 	// •	应用场景：适用于需要自定义初始价格或流动性的代币对。
 
     Offset,
+    /// Output always equals input before fees, for integration tests only
+    #[cfg(feature = "test-curve")]
+    Identity,
 }
 
 /// Encodes all results of swapping from a source token to a destination token
@@ -112,6 +123,43 @@ impl SwapCurve {
         })
     }
 
+    /// Growth of the curve invariant from a swap, leaving out what its fees
+    /// add. The curve rounds the output in the pool's favor, so the rounded
+    /// off dust stays in the reserves and accrues to the liquidity
+    /// providers. `None` if the curve has no invariant.
+    // 曲线向池子有利的方向取整，被舍去的零头留在储备中归 LP 所有，这里按不变量的增量计量
+    pub fn rounding_dust(
+        &self,
+        result: &SwapResult,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        let swap_source_amount = result
+            .new_swap_source_amount
+            .checked_sub(result.source_amount_swapped)?;
+        let swap_destination_amount = result
+            .new_swap_destination_amount
+            .checked_add(result.destination_amount_swapped)?;
+        let new_swap_source_amount = result
+            .new_swap_source_amount
+            .checked_sub(result.trade_fee)?
+            .checked_sub(result.owner_fee)?;
+        let (before, after) = match trade_direction {
+            TradeDirection::AtoB => (
+                self.calculator
+                    .invariant(swap_source_amount, swap_destination_amount)?,
+                self.calculator
+                    .invariant(new_swap_source_amount, result.new_swap_destination_amount)?,
+            ),
+            TradeDirection::BtoA => (
+                self.calculator
+                    .invariant(swap_destination_amount, swap_source_amount)?,
+                self.calculator
+                    .invariant(result.new_swap_destination_amount, new_swap_source_amount)?,
+            ),
+        };
+        after.checked_sub(before)
+    }
+
     /// Get the amount of pool tokens for the deposited amount of token A or B
     /// 单一类型代币的存入操作，它会先计算用户存入的代币所需支付的费用（包括交易费用和所有者费用），
     /// 然后使用交换协议的计算器根据存入的代币数量、池子的代币状态和费用计算出用户获得的池子代币数量。
@@ -132,9 +180,7 @@ impl SwapCurve {
         // https://github.com/balancer-labs/balancer-core/blob/f4ed5d65362a8d6cec21662fb6eae233b0babc1f/contracts/BMath.sol#L117
         // 计算交易费
         let half_source_amount = std::cmp::max(1, source_amount.checked_div(2)?);
-        let trade_fee = fees.trading_fee(half_source_amount)?;
-        let owner_fee = fees.owner_trading_fee(half_source_amount)?;
-        let total_fees = trade_fee.checked_add(owner_fee)?;
+        let total_fees = fees.effective_trade_fee(half_source_amount)?;
         let source_amount = source_amount.checked_sub(total_fees)?;
         self.calculator.deposit_single_token_type(
             source_amount,
@@ -176,6 +222,55 @@ impl SwapCurve {
             RoundDirection::Ceiling,
         )
     }
+
+    /// Token A and B amounts that `pool_token_amount` pool tokens, net of
+    /// any withdraw fee, are worth when withdrawing both types of tokens:
+    /// rounded down in the pool's favor and capped at the reserves.
+    // 双边提取（或清算）的价值：按份额向下取整，且不超过当前储备
+    pub fn withdraw_all_value(
+        &self,
+        pool_token_amount: u128,
+        pool_token_supply: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+    ) -> Option<TradingTokenResult> {
+        let results = self.calculator.pool_tokens_to_trading_tokens(
+            pool_token_amount,
+            pool_token_supply,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            RoundDirection::Floor,
+        )?;
+        Some(TradingTokenResult {
+            token_a_amount: results.token_a_amount.min(swap_token_a_amount),
+            token_b_amount: results.token_b_amount.min(swap_token_b_amount),
+        })
+    }
+}
+
+/// Curve registry: unpacks the calculator for the given curve type from its
+/// packed parameters. Adding a curve only requires a new arm here, along with
+/// the new `CurveType` variant.
+pub fn curve_from_type(
+    curve_type: CurveType,
+    bytes: &[u8],
+) -> Result<Arc<dyn CurveCalculator + Sync + Send>, SwapError> {
+    match curve_type {
+        CurveType::ConstantProduct => unpack_calculator::<ConstantProductCurve>(bytes),
+        CurveType::ConstantPrice => unpack_calculator::<ConstantPriceCurve>(bytes),
+        CurveType::Offset => unpack_calculator::<OffsetCurve>(bytes),
+        #[cfg(feature = "test-curve")]
+        CurveType::Identity => unpack_calculator::<IdentityCurve>(bytes),
+    }
+}
+
+fn unpack_calculator<T>(bytes: &[u8]) -> Result<Arc<dyn CurveCalculator + Sync + Send>, SwapError>
+where
+    T: CurveCalculator + Pack + Sync + Send + 'static,
+{
+    let bytes = bytes.get(..T::LEN).ok_or(SwapError::InvalidCurve)?;
+    let calculator = T::unpack_from_slice(bytes).map_err(|_| SwapError::InvalidCurve)?;
+    Ok(Arc::new(calculator))
 }
 
 /// Default implementation for SwapCurve cannot be derived because of
@@ -231,15 +326,7 @@ impl Pack for SwapCurve {
         let curve_type = curve_type[0].try_into()?;
         Ok(Self {
             curve_type,
-            calculator: match curve_type {
-                CurveType::ConstantProduct => {
-                    Arc::new(ConstantProductCurve::unpack_from_slice(calculator)?)
-                }
-                CurveType::ConstantPrice => {
-                    Arc::new(ConstantPriceCurve::unpack_from_slice(calculator)?)
-                }
-                CurveType::Offset => Arc::new(OffsetCurve::unpack_from_slice(calculator)?),
-            },
+            calculator: curve_from_type(curve_type, calculator)?,
         })
     }
 
@@ -268,6 +355,8 @@ impl TryFrom<u8> for CurveType {
             0 => Ok(CurveType::ConstantProduct),
             1 => Ok(CurveType::ConstantPrice),
             2 => Ok(CurveType::Offset),
+            #[cfg(feature = "test-curve")]
+            3 => Ok(CurveType::Identity),
             _ => Err(ProgramError::InvalidAccountData),
         }
     }
@@ -275,7 +364,14 @@ impl TryFrom<u8> for CurveType {
 
 #[cfg(test)]
 mod test {
-    use {super::*, crate::curve::calculator::test::total_and_intermediate, proptest::prelude::*};
+    use {
+        super::*,
+        crate::curve::calculator::{
+            test::{check_curve_baseline, total_and_intermediate},
+            INITIAL_SWAP_POOL_AMOUNT,
+        },
+        proptest::prelude::*,
+    };
 
     #[test]
     fn pack_swap_curve() {
@@ -297,6 +393,240 @@ mod test {
         assert_eq!(swap_curve, unpacked);
     }
 
+    #[test]
+    fn curve_registry_round_trip() {
+        let curves: Vec<(CurveType, Arc<dyn CurveCalculator + Sync + Send>)> = vec![
+            (
+                CurveType::ConstantProduct,
+                Arc::new(ConstantProductCurve {}),
+            ),
+            (
+                CurveType::ConstantPrice,
+                Arc::new(ConstantPriceCurve {
+                    token_b_price: 1_234,
+                }),
+            ),
+            (
+                CurveType::Offset,
+                Arc::new(OffsetCurve {
+                    token_b_offset: 5_678,
+                }),
+            ),
+        ];
+        for (curve_type, calculator) in curves {
+            let mut packed = [0u8; 32];
+            calculator.pack_into_slice(&mut packed);
+            let unpacked = curve_from_type(curve_type, &packed).unwrap();
+            let mut repacked = [0u8; 32];
+            unpacked.pack_into_slice(&mut repacked);
+            assert_eq!(packed, repacked);
+
+            let swap_curve = SwapCurve {
+                curve_type,
+                calculator,
+            };
+            let mut packed = [0u8; SwapCurve::LEN];
+            Pack::pack_into_slice(&swap_curve, &mut packed[..]);
+            assert_eq!(SwapCurve::unpack_from_slice(&packed).unwrap(), swap_curve);
+        }
+
+        // not enough bytes for the curve parameters
+        assert_eq!(
+            curve_from_type(CurveType::Offset, &[0u8; 4]).err(),
+            Some(SwapError::InvalidCurve)
+        );
+    }
+
+    #[test]
+    fn curves_meet_baseline() {
+        // every curve type gets the same battery, add new curves here
+        let curves: Vec<Arc<dyn CurveCalculator + Sync + Send>> = vec![
+            Arc::new(ConstantProductCurve {}),
+            Arc::new(ConstantPriceCurve { token_b_price: 2 }),
+            Arc::new(OffsetCurve {
+                token_b_offset: 5_678,
+            }),
+            #[cfg(feature = "test-curve")]
+            Arc::new(IdentityCurve {}),
+        ];
+        // reserve products are perfect squares, so that normalized values of
+        // the product curves are not skewed by the square root's precision
+        let reserves = [
+            (1_000_000_000_000, 1_000_000_000_000),
+            (4_000_000_000_000, 1_000_000_000_000),
+            (1_000_000_000_000, 4_000_000_000_000),
+        ];
+        for curve in curves {
+            for (swap_token_a_amount, swap_token_b_amount) in reserves {
+                check_curve_baseline(
+                    curve.as_ref(),
+                    100_000_000,
+                    swap_token_a_amount,
+                    swap_token_b_amount,
+                    12_345_679,
+                    INITIAL_SWAP_POOL_AMOUNT,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn withdraw_all_value_rounds_down_within_reserves() {
+        let swap_curve = SwapCurve {
+            curve_type: CurveType::ConstantProduct,
+            calculator: Arc::new(ConstantProductCurve {}),
+        };
+        // a third of 1_000 and 7_777, rounded down
+        assert_eq!(
+            swap_curve.withdraw_all_value(1, 3, 1_000, 7_777),
+            Some(TradingTokenResult {
+                token_a_amount: 333,
+                token_b_amount: 2_592,
+            })
+        );
+        // more pool tokens than the supply are worth no more than the reserves
+        assert_eq!(
+            swap_curve.withdraw_all_value(4, 3, 1_000, 7_777),
+            Some(TradingTokenResult {
+                token_a_amount: 1_000,
+                token_b_amount: 7_777,
+            })
+        );
+        assert_eq!(swap_curve.withdraw_all_value(1, 0, 1_000, 7_777), None);
+    }
+
+    #[test]
+    fn rounding_dust_leaves_out_fees() {
+        let swap_curve = SwapCurve {
+            curve_type: CurveType::ConstantProduct,
+            calculator: Arc::new(ConstantProductCurve {}),
+        };
+        let fees = Fees {
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 100,
+            owner_trade_fee_numerator: 1,
+            owner_trade_fee_denominator: 200,
+            ..Fees::default()
+        };
+        for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+            let (swap_source_amount, swap_destination_amount) = (1_000, 50_000);
+            let invariant = swap_source_amount * swap_destination_amount;
+
+            let result = swap_curve
+                .swap(
+                    100,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    trade_direction,
+                    &Fees::default(),
+                )
+                .unwrap();
+            // 4545.45 rounded down to 4545
+            assert_eq!(result.destination_amount_swapped, 4_545);
+            let dust = swap_curve.rounding_dust(&result, trade_direction).unwrap();
+            assert_eq!(
+                dust,
+                result.new_swap_source_amount * result.new_swap_destination_amount - invariant
+            );
+            assert_eq!(dust, 500);
+
+            // the fees stay in the pool too, but are not dust
+            let result = swap_curve
+                .swap(
+                    100,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    trade_direction,
+                    &fees,
+                )
+                .unwrap();
+            let new_source_amount_less_fees =
+                result.new_swap_source_amount - result.trade_fee - result.owner_fee;
+            let dust = swap_curve.rounding_dust(&result, trade_direction).unwrap();
+            assert_eq!(
+                dust,
+                new_source_amount_less_fees * result.new_swap_destination_amount - invariant
+            );
+            assert!(dust < new_source_amount_less_fees);
+        }
+
+        // a constant price swap from A to B is exact
+        let swap_curve = SwapCurve {
+            curve_type: CurveType::ConstantPrice,
+            calculator: Arc::new(ConstantPriceCurve { token_b_price: 10 }),
+        };
+        let result = swap_curve
+            .swap(
+                1_005,
+                10_000,
+                10_000,
+                TradeDirection::AtoB,
+                &Fees::default(),
+            )
+            .unwrap();
+        assert_eq!(result.source_amount_swapped, 1_000);
+        assert_eq!(
+            swap_curve.rounding_dust(&result, TradeDirection::AtoB),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn curve_type_discriminants() {
+        // 已部署池子的序列化依赖这些数值, 不能改变
+        assert_eq!(CurveType::ConstantProduct as u8, 0);
+        assert_eq!(CurveType::ConstantPrice as u8, 1);
+        assert_eq!(CurveType::Offset as u8, 2);
+
+        let curves: Vec<(u8, CurveType, Arc<dyn CurveCalculator + Sync + Send>)> = vec![
+            (
+                0,
+                CurveType::ConstantProduct,
+                Arc::new(ConstantProductCurve {}),
+            ),
+            (
+                1,
+                CurveType::ConstantPrice,
+                Arc::new(ConstantPriceCurve {
+                    token_b_price: 1_234,
+                }),
+            ),
+            (
+                2,
+                CurveType::Offset,
+                Arc::new(OffsetCurve {
+                    token_b_offset: 5_678,
+                }),
+            ),
+        ];
+        for (discriminant, curve_type, calculator) in curves {
+            assert_eq!(CurveType::try_from(discriminant).unwrap(), curve_type);
+
+            let swap_curve = SwapCurve {
+                curve_type,
+                calculator,
+            };
+            let mut packed = [0u8; SwapCurve::LEN];
+            Pack::pack_into_slice(&swap_curve, &mut packed[..]);
+            assert_eq!(packed[0], discriminant);
+            let unpacked = SwapCurve::unpack_from_slice(&packed).unwrap();
+            assert_eq!(unpacked.curve_type, curve_type);
+            assert_eq!(unpacked, swap_curve);
+        }
+
+        #[cfg(not(feature = "test-curve"))]
+        assert_eq!(
+            CurveType::try_from(3),
+            Err(ProgramError::InvalidAccountData)
+        );
+        #[cfg(feature = "test-curve")]
+        assert_eq!(CurveType::try_from(3).unwrap(), CurveType::Identity);
+        assert_eq!(
+            CurveType::try_from(4),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
     #[test]
     fn constant_product_trade_fee() {
         // calculation on https://github.com/solana-labs/solana-program-library/issues/341
@@ -10,6 +10,11 @@ use {crate::error::SwapError, spl_math::precise_number::PreciseNumber, std::fmt:
 /// input amounts, and Balancer uses 100 * 10 ^ 18.
 pub const INITIAL_SWAP_POOL_AMOUNT: u128 = 1_000_000_000;
 
+/// Fixed-point scale of the prices taken by
+/// [`CurveCalculator::depth_to_price`]: a price of token A in token B is
+/// given multiplied by this.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000;
+
 /// Hardcode the number of token types in a pool, used to calculate the
 /// equivalent pool tokens for the owner trading fee.
 pub const TOKENS_IN_POOL: u128 = 2;
@@ -89,7 +94,7 @@ pub trait DynPack {
 	// 3.	LP 代币兑换交易代币（pool_tokens_to_trading_tokens）。
 	// 4.	单边存款和取款计算（deposit_single_token_type、withdraw_single_token_type_exact_out）。
 	// 5.	曲线参数验证（validate、validate_supply）。
-	// 6.	是否允许后续存款（allows_deposits）。
+	// 6.	是否允许后续存款（allows_deposits）和取款（allows_withdrawals）。
 	// 7.	计算曲线的总价值（normalized_value）。
 pub trait CurveCalculator: Debug + DynPack {
     /// Calculate how much destination token will be provided given an amount
@@ -111,6 +116,67 @@ pub trait CurveCalculator: Debug + DynPack {
         trade_direction: TradeDirection,
     ) -> Option<SwapWithoutFeesResult>;
 
+    /// Calculate how much destination token the given amount of source token
+    /// is worth at the current spot price, ignoring the price impact of the
+    /// trade itself. The default implementation prices by the ratio of the
+    /// reserves, as on a constant product curve.
+    ///
+    /// Returns `None` if either reserve is zero, since the pool then has no
+    /// spot price.
+    // 按当前现货价格计算输出量（不考虑交易本身对价格的影响），与 swap_without_fees 的结果对比即可得到价格影响。
+    fn spot_amount_out(
+        &self,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        _trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        // 储备为零时没有现货价格，返回 None 而不是除以零
+        let swap_source_amount = map_zero_to_none(swap_source_amount)?;
+        let swap_destination_amount = map_zero_to_none(swap_destination_amount)?;
+        source_amount
+            .checked_mul(swap_destination_amount)?
+            .checked_div(swap_source_amount)
+    }
+
+    /// Calculate how much source token must be swapped in, before fees, to
+    /// move the pool's price of token A in token B to `target_price`, scaled
+    /// by [`PRICE_SCALE`]. Selling token A (`AtoB`) lowers the price and
+    /// selling token B raises it.
+    ///
+    /// Returns `None` if the target cannot be reached in the given
+    /// direction, or if the curve does not support the calculation, which
+    /// is the default.
+    // 计算把价格推到目标价格需要投入多少源代币，供前端展示流动性深度
+    fn depth_to_price(
+        &self,
+        _reserve_a: u128,
+        _reserve_b: u128,
+        _target_price: u128,
+        _trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        None
+    }
+
+    /// The curve's invariant for the given reserves, e.g. `k = a * b` for the
+    /// constant product curve. Swaps without fees keep it constant up to
+    /// rounding in the pool's favor, and fees retained in the pool grow it.
+    /// Curves without a closed-form invariant return `None`, which is the
+    /// default.
+    // 曲线不变量，可用于监控池子在多次交换之间的健康状况
+    fn invariant(&self, _reserve_a: u128, _reserve_b: u128) -> Option<u128> {
+        None
+    }
+
+    /// Ratio of token A to token B in which `DepositAllTokenTypes` takes
+    /// both tokens, so that the deposit does not move the price. The
+    /// default implementation is the ratio of the reserves, as for any
+    /// curve that deposits proportionally to them. The ratio is not reduced.
+    // 双边存款时两种代币的投入比例，前端可据此预填金额
+    fn deposit_ratio(&self, reserve_a: u128, reserve_b: u128) -> (u128, u128) {
+        (reserve_a, reserve_b)
+    }
+
     /// Get the supply for a new pool
     /// The default implementation is a Balancer-style fixed initial supply
     /// 获取新池子的初始流动性供应量，默认值是 INITIAL_SWAP_POOL_AMOUNT（通常是 Balancer 风格的固定初始供应量）。
@@ -231,6 +297,13 @@ pub trait CurveCalculator: Debug + DynPack {
         true
     }
 
+    /// Some curves, such as one-way bonding curves, never give liquidity
+    /// back once it is deposited.  By default, withdrawals are allowed.
+    /// 与 allows_deposits 对应：返回 false 时池子拒绝取款操作。
+    fn allows_withdrawals(&self) -> bool {
+        true
+    }
+
     /// Calculates the total normalized value of the curve given the liquidity
     /// parameters.
     ///
@@ -599,6 +672,167 @@ pub mod test {
             .greater_than_or_equal(&value.checked_mul(&new_pool_token_supply).unwrap()));
     }
 
+    /// Test function running the baseline battery every curve must pass, so
+    /// that all curves can be held to the same checks under identical inputs:
+    /// swaps in both directions, balanced and one-sided deposits and
+    /// withdrawals, and round trips through each of them.
+    ///
+    /// None of these may create value for the user, and pricing must be
+    /// monotonic: more input never yields less output, and a second trade in
+    /// the same direction never gets a better price than the first, up to one
+    /// token of rounding.
+    pub fn check_curve_baseline(
+        curve: &dyn CurveCalculator,
+        source_token_amount: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_token_amount: u128,
+        pool_token_supply: u128,
+    ) {
+        for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+            let (swap_source_amount, swap_destination_amount) = match trade_direction {
+                TradeDirection::AtoB => (swap_token_a_amount, swap_token_b_amount),
+                TradeDirection::BtoA => (swap_token_b_amount, swap_token_a_amount),
+            };
+            check_curve_value_from_swap(
+                curve,
+                source_token_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                trade_direction,
+            );
+
+            // monotonic pricing
+            let first = curve
+                .swap_without_fees(
+                    source_token_amount,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    trade_direction,
+                )
+                .unwrap();
+            let doubled = curve
+                .swap_without_fees(
+                    source_token_amount * 2,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    trade_direction,
+                )
+                .unwrap();
+            assert!(doubled.destination_amount_swapped >= first.destination_amount_swapped);
+            assert!(doubled.destination_amount_swapped <= first.destination_amount_swapped * 2 + 1);
+            let swap_source_amount = swap_source_amount + first.source_amount_swapped;
+            let swap_destination_amount =
+                swap_destination_amount - first.destination_amount_swapped;
+            let second = curve
+                .swap_without_fees(
+                    source_token_amount,
+                    swap_source_amount,
+                    swap_destination_amount,
+                    trade_direction,
+                )
+                .unwrap();
+            assert!(second.destination_amount_swapped <= first.destination_amount_swapped + 1);
+
+            // swapping the output straight back returns at most the input
+            let back = curve
+                .swap_without_fees(
+                    first.destination_amount_swapped,
+                    swap_destination_amount,
+                    swap_source_amount,
+                    trade_direction.opposite(),
+                )
+                .unwrap();
+            assert!(back.source_amount_swapped <= first.destination_amount_swapped);
+            assert!(back.destination_amount_swapped <= first.source_amount_swapped);
+
+            // a one-sided deposit costs at least as many pool tokens to take
+            // back out
+            let pool_tokens = curve
+                .deposit_single_token_type(
+                    source_token_amount,
+                    swap_token_a_amount,
+                    swap_token_b_amount,
+                    pool_token_supply,
+                    trade_direction,
+                )
+                .unwrap();
+            let (new_swap_token_a_amount, new_swap_token_b_amount) = match trade_direction {
+                TradeDirection::AtoB => (
+                    swap_token_a_amount + source_token_amount,
+                    swap_token_b_amount,
+                ),
+                TradeDirection::BtoA => (
+                    swap_token_a_amount,
+                    swap_token_b_amount + source_token_amount,
+                ),
+            };
+            let burned = curve
+                .withdraw_single_token_type_exact_out(
+                    source_token_amount,
+                    new_swap_token_a_amount,
+                    new_swap_token_b_amount,
+                    pool_token_supply + pool_tokens,
+                    trade_direction,
+                    RoundDirection::Ceiling,
+                )
+                .unwrap();
+            assert!(burned >= pool_tokens);
+        }
+
+        check_pool_value_from_withdraw(
+            curve,
+            pool_token_amount,
+            pool_token_supply,
+            swap_token_a_amount,
+            swap_token_b_amount,
+        );
+
+        // a balanced deposit never dilutes the value of pool tokens, compared
+        // by value since not every curve deposits in the reserve ratio
+        let deposited = curve
+            .pool_tokens_to_trading_tokens(
+                pool_token_amount,
+                pool_token_supply,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                RoundDirection::Ceiling,
+            )
+            .unwrap();
+        let value = curve
+            .normalized_value(swap_token_a_amount, swap_token_b_amount)
+            .unwrap();
+        let new_value = curve
+            .normalized_value(
+                swap_token_a_amount + deposited.token_a_amount,
+                swap_token_b_amount + deposited.token_b_amount,
+            )
+            .unwrap();
+        assert!(new_value
+            .checked_mul(&PreciseNumber::new(pool_token_supply).unwrap())
+            .unwrap()
+            .greater_than_or_equal(
+                &value
+                    .checked_mul(
+                        &PreciseNumber::new(pool_token_supply + pool_token_amount).unwrap()
+                    )
+                    .unwrap()
+            ));
+
+        // and withdrawn right away returns at most what went in
+        let withdrawn = curve
+            .pool_tokens_to_trading_tokens(
+                pool_token_amount,
+                pool_token_supply + pool_token_amount,
+                swap_token_a_amount + deposited.token_a_amount,
+                swap_token_b_amount + deposited.token_b_amount,
+                RoundDirection::Floor,
+            )
+            .unwrap();
+        assert!(withdrawn.token_a_amount <= deposited.token_a_amount);
+        assert!(withdrawn.token_b_amount <= deposited.token_b_amount);
+    }
+
     prop_compose! {
         pub fn total_and_intermediate(max_value: u64)(total in 1..max_value)
                         (intermediate in 1..total, total in Just(total))
@@ -9,6 +9,7 @@ use {
     },
     arrayref::{array_mut_ref, array_ref},
     solana_program::{
+        msg,
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack, Sealed},
     },
@@ -113,6 +114,24 @@ impl CurveCalculator for ConstantPriceCurve {
         })
     }
 
+    /// The constant price curve has no price impact, so the spot amount is
+    /// exactly what a swap yields
+    fn spot_amount_out(
+        &self,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        self.swap_without_fees(
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+            trade_direction,
+        )
+        .map(|result| result.destination_amount_swapped)
+    }
+
     /// Get the amount of trading tokens for the given amount of pool tokens,
     /// provided the total trading tokens and supply of pool tokens.
     /// For the constant price curve, the total value of the pool is weighted
@@ -205,9 +224,24 @@ impl CurveCalculator for ConstantPriceCurve {
         )
     }
 
+    /// Deposits take one token B for every `token_b_price` token A,
+    /// whatever the reserves
+    fn deposit_ratio(&self, _reserve_a: u128, _reserve_b: u128) -> (u128, u128) {
+        (u128::from(self.token_b_price), 1)
+    }
+
+    /// The invariant is the value of the reserves in token A,
+    /// `a + b * token_b_price`
+    fn invariant(&self, reserve_a: u128, reserve_b: u128) -> Option<u128> {
+        reserve_b
+            .checked_mul(u128::from(self.token_b_price))?
+            .checked_add(reserve_a)
+    }
+
     fn validate(&self) -> Result<(), SwapError> {
         if self.token_b_price == 0 {
-            Err(SwapError::InvalidCurve)
+            msg!("Constant price curve: token B price must be non-zero");
+            Err(SwapError::CurveValidationFailed)
         } else {
             Ok(())
         }
@@ -334,6 +368,41 @@ mod tests {
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn invariant_is_value_in_token_a() {
+        let curve = ConstantPriceCurve { token_b_price: 25 };
+        assert_eq!(curve.invariant(1_000, 40), Some(2_000));
+        // swapping at the constant price moves value between the sides only
+        let results = curve
+            .swap_without_fees(4, 40, 1_000, TradeDirection::BtoA)
+            .unwrap();
+        assert_eq!(
+            curve.invariant(
+                1_000 - results.destination_amount_swapped,
+                40 + results.source_amount_swapped
+            ),
+            Some(2_000)
+        );
+    }
+
+    #[test]
+    fn deposit_ratio_matches_trading_tokens() {
+        let token_b_price = 25;
+        let curve = ConstantPriceCurve { token_b_price };
+        // the ratio follows the price, not the reserves
+        for (reserve_a, reserve_b) in [(1_000, 3_000), (50_000, 40)] {
+            let (ratio_a, ratio_b) = curve.deposit_ratio(reserve_a, reserve_b);
+            assert_eq!((ratio_a, ratio_b), (u128::from(token_b_price), 1));
+            let results = curve
+                .pool_tokens_to_trading_tokens(10, 100, reserve_a, reserve_b, RoundDirection::Floor)
+                .unwrap();
+            assert_eq!(
+                results.token_a_amount * ratio_b,
+                results.token_b_amount * ratio_a
+            );
+        }
+    }
+
     #[test]
     fn pack_flat_curve() {
         let token_b_price = 1_251_258;
@@ -350,6 +419,14 @@ mod tests {
         assert_eq!(curve, unpacked);
     }
 
+    #[test]
+    fn validate_price() {
+        let curve = ConstantPriceCurve { token_b_price: 0 };
+        assert_eq!(curve.validate(), Err(SwapError::CurveValidationFailed));
+        let curve = ConstantPriceCurve { token_b_price: 1 };
+        assert_eq!(curve.validate(), Ok(()));
+    }
+
     #[test]
     fn swap_calculation_large_price() {
         let token_b_price = 1123513u128;
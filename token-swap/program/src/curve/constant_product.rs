@@ -2,9 +2,12 @@
 
 use {
     crate::{
-        curve::calculator::{
-            map_zero_to_none, CurveCalculator, DynPack, RoundDirection, SwapWithoutFeesResult,
-            TradeDirection, TradingTokenResult,
+        curve::{
+            calculator::{
+                map_zero_to_none, CurveCalculator, DynPack, RoundDirection,
+                SwapWithoutFeesResult, TradeDirection, TradingTokenResult, PRICE_SCALE,
+            },
+            math::constant_product_swap,
         },
         error::SwapError,
     },
@@ -12,7 +15,7 @@ use {
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack, Sealed},
     },
-    spl_math::{checked_ceil_div::CheckedCeilDiv, precise_number::PreciseNumber},
+    spl_math::{precise_number::PreciseNumber, uint::U256},
 };
 
 /// ConstantProductCurve struct implementing CurveCalculator
@@ -21,6 +24,8 @@ pub struct ConstantProductCurve;
 
 /// The constant product swap calculation, factored out of its class for reuse.
 ///
+/// The arithmetic is `math::constant_product_swap`, which clients share.
+///
 /// This is guaranteed to work for all values such that:
 ///  - 1 <= swap_source_amount * swap_destination_amount <= u128::MAX
 ///  - 1 <= source_amount <= u64::MAX
@@ -44,16 +49,8 @@ pub fn swap(
     swap_source_amount: u128,
     swap_destination_amount: u128,
 ) -> Option<SwapWithoutFeesResult> {
-    let invariant = swap_source_amount.checked_mul(swap_destination_amount)?;
-
-    let new_swap_source_amount = swap_source_amount.checked_add(source_amount)?;
-    let (new_swap_destination_amount, new_swap_source_amount) =
-        invariant.checked_ceil_div(new_swap_source_amount)?;
-
-    let source_amount_swapped = new_swap_source_amount.checked_sub(swap_source_amount)?;
-    let destination_amount_swapped =
-        map_zero_to_none(swap_destination_amount.checked_sub(new_swap_destination_amount)?)?;
-
+    let (source_amount_swapped, destination_amount_swapped) =
+        constant_product_swap(source_amount, swap_source_amount, swap_destination_amount)?;
     Some(SwapWithoutFeesResult {
         source_amount_swapped,
         destination_amount_swapped,
@@ -229,6 +226,39 @@ pub fn normalized_value(
         .sqrt()
 }
 
+/// Calculates how much source token a swap must take in, before fees, to move
+/// the price of token A in token B to `target_price`, scaled by `PRICE_SCALE`.
+///
+/// The reserves after the swap keep the invariant `a' * b' = a * b` and have
+/// `b' / a' = price`, so `a' = sqrt(a * b / price)` and
+/// `b' = sqrt(a * b * price)`, rounded up. The price after the swap matches
+/// the target up to the rounding of the swapped amounts.
+pub fn depth_to_price(
+    reserve_a: u128,
+    reserve_b: u128,
+    target_price: u128,
+    trade_direction: TradeDirection,
+) -> Option<u128> {
+    let target_price = U256::from(map_zero_to_none(target_price)?);
+    let invariant = U256::from(reserve_a).checked_mul(U256::from(reserve_b))?;
+    let scale = U256::from(PRICE_SCALE);
+    // 交易后储备的平方，向上取整
+    let (reserve, numerator, denominator) = match trade_direction {
+        TradeDirection::AtoB => (reserve_a, invariant.checked_mul(scale)?, target_price),
+        TradeDirection::BtoA => (reserve_b, invariant.checked_mul(target_price)?, scale),
+    };
+    let new_reserve_squared = numerator
+        .checked_add(denominator.checked_sub(U256::one())?)?
+        .checked_div(denominator)?;
+    let mut new_reserve = new_reserve_squared.integer_sqrt();
+    if new_reserve.checked_mul(new_reserve)? < new_reserve_squared {
+        new_reserve = new_reserve.checked_add(U256::one())?;
+    }
+    // 目标价格在反方向时无法到达
+    let source_amount = new_reserve.checked_sub(U256::from(reserve))?;
+    u128::try_from(source_amount).ok()
+}
+
 impl CurveCalculator for ConstantProductCurve {
     /// Constant product swap ensures x * y = constant
     fn swap_without_fees(
@@ -241,6 +271,17 @@ impl CurveCalculator for ConstantProductCurve {
         swap(source_amount, swap_source_amount, swap_destination_amount)
     }
 
+    /// The constant product curve has a closed form for the depth
+    fn depth_to_price(
+        &self,
+        reserve_a: u128,
+        reserve_b: u128,
+        target_price: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        depth_to_price(reserve_a, reserve_b, target_price, trade_direction)
+    }
+
     /// The constant product implementation is a simple ratio calculation for
     /// how many trading tokens correspond to a certain number of pool
     /// tokens
@@ -307,6 +348,11 @@ impl CurveCalculator for ConstantProductCurve {
         normalized_value(swap_token_a_amount, swap_token_b_amount)
     }
 
+    /// The invariant is the product of the reserves, `k = a * b`
+    fn invariant(&self, reserve_a: u128, reserve_b: u128) -> Option<u128> {
+        reserve_a.checked_mul(reserve_b)
+    }
+
     fn validate(&self) -> Result<(), SwapError> {
         Ok(())
     }
@@ -437,6 +483,55 @@ mod tests {
         assert!(new_invariant >= invariant);
     }
 
+    #[test]
+    fn spot_amount_out_zero_reserve() {
+        let curve = ConstantProductCurve;
+        assert_eq!(
+            curve.spot_amount_out(100, 1_000, 2_000, TradeDirection::AtoB),
+            Some(200)
+        );
+        assert_eq!(curve.spot_amount_out(100, 0, 2_000, TradeDirection::AtoB), None);
+        assert_eq!(curve.spot_amount_out(100, 1_000, 0, TradeDirection::AtoB), None);
+    }
+
+    #[test]
+    fn invariant_never_decreases_from_swap() {
+        let curve = ConstantProductCurve;
+        let (swap_token_a_amount, swap_token_b_amount) = (1_000_000, 3_000_000);
+        let k = curve
+            .invariant(swap_token_a_amount, swap_token_b_amount)
+            .unwrap();
+        assert_eq!(k, 3_000_000_000_000);
+        let results = curve
+            .swap_without_fees(
+                12_345,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                TradeDirection::AtoB,
+            )
+            .unwrap();
+        let new_k = curve
+            .invariant(
+                swap_token_a_amount + results.source_amount_swapped,
+                swap_token_b_amount - results.destination_amount_swapped,
+            )
+            .unwrap();
+        // rounding only ever favors the pool, by less than one token B
+        assert!(new_k >= k);
+        assert!(new_k - k < swap_token_a_amount + results.source_amount_swapped);
+        assert_eq!(curve.invariant(u128::MAX, 2), None);
+    }
+
+    #[test]
+    fn deposit_ratio_matches_trading_tokens() {
+        let curve = ConstantProductCurve;
+        assert_eq!(curve.deposit_ratio(1_000, 3_000), (1_000, 3_000));
+        let results = curve
+            .pool_tokens_to_trading_tokens(10, 100, 1_000, 3_000, RoundDirection::Floor)
+            .unwrap();
+        assert_eq!(results.token_a_amount * 3_000, results.token_b_amount * 1_000);
+    }
+
     #[test]
     fn constant_product_swap_rounding() {
         let curve = ConstantProductCurve;
@@ -619,4 +714,161 @@ mod tests {
             );
         }
     }
+
+    proptest! {
+        #[test]
+        fn repeated_tiny_withdraws_stay_within_entitlement(
+            pool_token_supply in 1_000_000..u64::MAX,
+            swap_token_a_amount in 1..u64::MAX,
+            swap_token_b_amount in 1..u64::MAX,
+            pool_token_amount in 1..1_000u128,
+            withdraw_count in 1..200u128,
+        ) {
+            let curve = ConstantProductCurve {};
+            let pool_token_supply = pool_token_supply as u128;
+            let swap_token_a_amount = swap_token_a_amount as u128;
+            let swap_token_b_amount = swap_token_b_amount as u128;
+            let mut supply = pool_token_supply;
+            let mut token_a_amount = swap_token_a_amount;
+            let mut token_b_amount = swap_token_b_amount;
+            let (mut burned, mut withdrawn_a, mut withdrawn_b) = (0, 0, 0);
+            for _ in 0..withdraw_count {
+                let result = curve
+                    .pool_tokens_to_trading_tokens(
+                        pool_token_amount,
+                        supply,
+                        token_a_amount,
+                        token_b_amount,
+                        RoundDirection::Floor,
+                    )
+                    .unwrap();
+                // as in the processor: capped by the reserves, and rejected,
+                // keeping the pool tokens, if either side rounds to nothing
+                let a = result.token_a_amount.min(token_a_amount);
+                let b = result.token_b_amount.min(token_b_amount);
+                if (a == 0 && token_a_amount != 0) || (b == 0 && token_b_amount != 0) {
+                    continue;
+                }
+                supply -= pool_token_amount;
+                token_a_amount -= a;
+                token_b_amount -= b;
+                burned += pool_token_amount;
+                withdrawn_a += a;
+                withdrawn_b += b;
+            }
+            // never more than the same pool tokens withdrawn at once, exactly
+            prop_assert!(withdrawn_a <= swap_token_a_amount * burned / pool_token_supply);
+            prop_assert!(withdrawn_b <= swap_token_b_amount * burned / pool_token_supply);
+        }
+    }
+
+    fn check_depth_to_price(
+        reserve_a: u128,
+        reserve_b: u128,
+        target_price: u128,
+        trade_direction: TradeDirection,
+    ) {
+        let curve = ConstantProductCurve {};
+        let source_amount = curve
+            .depth_to_price(reserve_a, reserve_b, target_price, trade_direction)
+            .unwrap();
+        let (swap_source_amount, swap_destination_amount) = match trade_direction {
+            TradeDirection::AtoB => (reserve_a, reserve_b),
+            TradeDirection::BtoA => (reserve_b, reserve_a),
+        };
+        let result = curve
+            .swap_without_fees(
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                trade_direction,
+            )
+            .unwrap();
+        let new_source = swap_source_amount + result.source_amount_swapped;
+        let new_destination = swap_destination_amount - result.destination_amount_swapped;
+        let (new_a, new_b) = match trade_direction {
+            TradeDirection::AtoB => (new_source, new_destination),
+            TradeDirection::BtoA => (new_destination, new_source),
+        };
+        // the price after the swap, b / a, is the target up to the rounding
+        // of a unit or two of either reserve:
+        // |b * scale - target * a| / (target * a) <= 2 / a + 2 / b
+        let new_a = U256::from(new_a);
+        let new_b = U256::from(new_b);
+        let target_price = U256::from(target_price);
+        let price = new_b * U256::from(PRICE_SCALE);
+        let target = target_price * new_a;
+        let difference = if price > target {
+            price - target
+        } else {
+            target - price
+        };
+        assert!(difference * new_b <= U256::from(2) * target_price * (new_a + new_b));
+    }
+
+    #[test]
+    fn depth_to_price() {
+        // price of 2 B per A, moved down to 1.5 and up to 3
+        check_depth_to_price(
+            1_000_000,
+            2_000_000,
+            PRICE_SCALE * 3 / 2,
+            TradeDirection::AtoB,
+        );
+        check_depth_to_price(1_000_000, 2_000_000, PRICE_SCALE * 3, TradeDirection::BtoA);
+        check_depth_to_price(
+            5_000_000_000,
+            1_000_000_000_000,
+            PRICE_SCALE * 100,
+            TradeDirection::AtoB,
+        );
+        check_depth_to_price(
+            5_000_000_000,
+            1_000_000_000_000,
+            PRICE_SCALE * 300,
+            TradeDirection::BtoA,
+        );
+
+        let curve = ConstantProductCurve {};
+        // already at the target price
+        assert_eq!(
+            curve.depth_to_price(1_000, 2_000, PRICE_SCALE * 2, TradeDirection::AtoB),
+            Some(0)
+        );
+        // selling token A cannot raise the price
+        assert_eq!(
+            curve.depth_to_price(1_000, 2_000, PRICE_SCALE * 3, TradeDirection::AtoB),
+            None
+        );
+        assert_eq!(
+            curve.depth_to_price(1_000, 2_000, PRICE_SCALE, TradeDirection::BtoA),
+            None
+        );
+        assert_eq!(
+            curve.depth_to_price(1_000, 2_000, 0, TradeDirection::AtoB),
+            None
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn depth_to_price_reaches_target(
+            reserve_a in 1_000..u64::MAX,
+            reserve_b in 1_000..u64::MAX,
+            target_numerator in 1..1_000u128,
+            target_denominator in 1..1_000u128,
+        ) {
+            let reserve_a = reserve_a as u128;
+            let reserve_b = reserve_b as u128;
+            let current_price = reserve_b * PRICE_SCALE / reserve_a;
+            let target_price = current_price * target_numerator / target_denominator;
+            prop_assume!(target_price > 0 && target_price != current_price);
+            let trade_direction = if target_price < current_price {
+                TradeDirection::AtoB
+            } else {
+                TradeDirection::BtoA
+            };
+            check_depth_to_price(reserve_a, reserve_b, target_price, trade_direction);
+        }
+    }
 }
@@ -1,7 +1,11 @@
 //! All fee information, to be used for validation currently
 
+pub use crate::curve::math::calculate_fee;
 use {
-    crate::error::SwapError,
+    crate::{
+        curve::math::{pre_fee_amount, TradeFees},
+        error::SwapError,
+    },
     arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs},
     solana_program::{
         program_error::ProgramError,
@@ -71,53 +75,6 @@ pub struct Fees {
     pub host_fee_denominator: u64,
 }
 
-/// Helper function for calculating swap fee
-/// 	1.	如果手续费分子或代币金额为 0，则没有手续费，返回 Some(0)。
-// 2.	使用 checked_mul 和 checked_div 来计算手续费，确保计算过程中没有溢出或除零错误。
-// 3.	如果计算出的手续费为 0，返回至少 1 个代币的手续费（作为最低手续费）。
-// 4.	如果计算出的手续费大于 0，则返回实际计算的手续费。
-pub fn calculate_fee(
-    token_amount: u128,
-    fee_numerator: u128,
-    fee_denominator: u128,
-) -> Option<u128> {
-    if fee_numerator == 0 || token_amount == 0 {
-        Some(0)
-    } else {
-        let fee = token_amount
-            .checked_mul(fee_numerator)?
-            .checked_div(fee_denominator)?;
-        if fee == 0 {
-            Some(1) // minimum fee of one token
-        } else {
-            Some(fee)
-        }
-    }
-}
-
-fn ceil_div(dividend: u128, divisor: u128) -> Option<u128> {
-    dividend
-        .checked_add(divisor)?
-        .checked_sub(1)?
-        .checked_div(divisor)
-}
-
-fn pre_fee_amount(
-    post_fee_amount: u128,
-    fee_numerator: u128,
-    fee_denominator: u128,
-) -> Option<u128> {
-    if fee_numerator == 0 || fee_denominator == 0 {
-        Some(post_fee_amount)
-    } else if fee_numerator == fee_denominator || post_fee_amount == 0 {
-        Some(0)
-    } else {
-        let numerator = post_fee_amount.checked_mul(fee_denominator)?;
-        let denominator = fee_denominator.checked_sub(fee_numerator)?;
-        ceil_div(numerator, denominator)
-    }
-}
-
 fn validate_fraction(numerator: u64, denominator: u64) -> Result<(), SwapError> {
     if denominator == 0 && numerator == 0 {
         Ok(())
@@ -156,6 +113,23 @@ impl Fees {
         )
     }
 
+    /// The trading fees alone, as the client quote math takes them
+    pub fn trade_fees(&self) -> TradeFees {
+        TradeFees {
+            trade_fee_numerator: self.trade_fee_numerator,
+            trade_fee_denominator: self.trade_fee_denominator,
+            owner_trade_fee_numerator: self.owner_trade_fee_numerator,
+            owner_trade_fee_denominator: self.owner_trade_fee_denominator,
+        }
+    }
+
+    /// Calculate the total fee taken from a trade's input, the trading fee
+    /// plus the owner trading fee, each rounded as they are charged
+    pub fn effective_trade_fee(&self, trading_tokens: u128) -> Option<u128> {
+        self.trading_fee(trading_tokens)?
+            .checked_add(self.owner_trading_fee(trading_tokens)?)
+    }
+
     /// Calculate the inverse trading amount, how much input is needed to give
     /// the provided output
     pub fn pre_trading_fee_amount(&self, post_fee_amount: u128) -> Option<u128> {
@@ -196,6 +170,20 @@ impl Fees {
         )
     }
 
+    /// Fees with both trading fees reduced by `discount_bps` basis points.
+    /// The denominators are scaled instead of rounding the numerators, so
+    /// small fees keep their precision.
+    pub fn with_trade_fee_discount(&self, discount_bps: u16) -> Option<Fees> {
+        let remaining = 10_000u64.checked_sub(u64::from(discount_bps))?;
+        Some(Fees {
+            trade_fee_numerator: self.trade_fee_numerator.checked_mul(remaining)?,
+            trade_fee_denominator: self.trade_fee_denominator.checked_mul(10_000)?,
+            owner_trade_fee_numerator: self.owner_trade_fee_numerator.checked_mul(remaining)?,
+            owner_trade_fee_denominator: self.owner_trade_fee_denominator.checked_mul(10_000)?,
+            ..self.clone()
+        })
+    }
+
     /// Validate that the fees are reasonable
     pub fn validate(&self) -> Result<(), SwapError> {
         validate_fraction(self.trade_fee_numerator, self.trade_fee_denominator)?;
@@ -312,4 +300,50 @@ mod tests {
         let unpacked = Fees::unpack_from_slice(&packed).unwrap();
         assert_eq!(fees, unpacked);
     }
+
+    #[test]
+    fn trading_fees() {
+        // (trade fee, owner trade fee, amount, trade fee, owner fee)
+        for (trade, owner, amount, trade_fee, owner_fee) in [
+            ((0, 0), (0, 0), 1_000_000, 0, 0),
+            ((0, 10_000), (0, 10_000), 1_000_000, 0, 0),
+            ((25, 10_000), (5, 10_000), 1_000_000, 2_500, 500),
+            ((25, 10_000), (5, 10_000), 0, 0, 0),
+            // below one token, rounded up to the minimum fee of one
+            ((25, 10_000), (5, 10_000), 100, 1, 1),
+            ((1, 4), (2, 5), 1_000, 250, 400),
+            // rounded down above one token
+            ((1, 3), (1, 7), 100, 33, 14),
+            (
+                (3, 1_000),
+                (0, 0),
+                u64::MAX as u128,
+                55_340_232_221_128_654,
+                0,
+            ),
+        ] {
+            let fees = Fees {
+                trade_fee_numerator: trade.0,
+                trade_fee_denominator: trade.1,
+                owner_trade_fee_numerator: owner.0,
+                owner_trade_fee_denominator: owner.1,
+                ..Fees::default()
+            };
+            assert_eq!(fees.trading_fee(amount), Some(trade_fee));
+            assert_eq!(fees.owner_trading_fee(amount), Some(owner_fee));
+            assert_eq!(
+                fees.effective_trade_fee(amount),
+                Some(trade_fee + owner_fee)
+            );
+        }
+
+        // a zero denominator with a non-zero numerator cannot be computed
+        let fees = Fees {
+            trade_fee_numerator: 1,
+            ..Fees::default()
+        };
+        assert_eq!(fees.trading_fee(100), None);
+        assert_eq!(fees.effective_trade_fee(100), None);
+        assert_eq!(fees.owner_trading_fee(100), Some(0));
+    }
 }
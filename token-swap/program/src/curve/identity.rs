@@ -0,0 +1,282 @@
+//! Deterministic 1:1 curve, only available with the `test-curve` feature
+
+use {
+    crate::{
+        curve::{
+            calculator::{
+                map_zero_to_none, CurveCalculator, DynPack, RoundDirection, SwapWithoutFeesResult,
+                TradeDirection, TradingTokenResult,
+            },
+            constant_price::trading_tokens_to_pool_tokens,
+            constant_product::pool_tokens_to_trading_tokens,
+        },
+        error::SwapError,
+    },
+    solana_program::{
+        program_error::ProgramError,
+        program_pack::{IsInitialized, Pack, Sealed},
+    },
+    spl_math::precise_number::PreciseNumber,
+};
+
+/// IdentityCurve struct implementing CurveCalculator
+///
+/// Every swap gives out exactly what goes in, regardless of the reserves, so
+/// anything taken from a trade can only have come from the fees. Meant for
+/// integration tests of the processor, never for deployed pools.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IdentityCurve;
+
+impl CurveCalculator for IdentityCurve {
+    /// Identity swap always returns the source amount
+    fn swap_without_fees(
+        &self,
+        source_amount: u128,
+        _swap_source_amount: u128,
+        _swap_destination_amount: u128,
+        _trade_direction: TradeDirection,
+    ) -> Option<SwapWithoutFeesResult> {
+        let amount = map_zero_to_none(source_amount)?;
+        Some(SwapWithoutFeesResult {
+            source_amount_swapped: amount,
+            destination_amount_swapped: amount,
+        })
+    }
+
+    /// No price impact, the spot amount is the source amount
+    fn spot_amount_out(
+        &self,
+        source_amount: u128,
+        _swap_source_amount: u128,
+        _swap_destination_amount: u128,
+        _trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        Some(source_amount)
+    }
+
+    /// Pool tokens are redeemed proportionally to the reserves
+    fn pool_tokens_to_trading_tokens(
+        &self,
+        pool_tokens: u128,
+        pool_token_supply: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        round_direction: RoundDirection,
+    ) -> Option<TradingTokenResult> {
+        pool_tokens_to_trading_tokens(
+            pool_tokens,
+            pool_token_supply,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            round_direction,
+        )
+    }
+
+    /// Both tokens are worth the same, as for a constant price of 1
+    fn deposit_single_token_type(
+        &self,
+        source_amount: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        trading_tokens_to_pool_tokens(
+            1,
+            source_amount,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            pool_supply,
+            trade_direction,
+            RoundDirection::Floor,
+        )
+    }
+
+    fn withdraw_single_token_type_exact_out(
+        &self,
+        source_amount: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+        round_direction: RoundDirection,
+    ) -> Option<u128> {
+        trading_tokens_to_pool_tokens(
+            1,
+            source_amount,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            pool_supply,
+            trade_direction,
+            round_direction,
+        )
+    }
+
+    /// The invariant is the sum of the reserves
+    fn invariant(&self, reserve_a: u128, reserve_b: u128) -> Option<u128> {
+        reserve_a.checked_add(reserve_b)
+    }
+
+    fn validate(&self) -> Result<(), SwapError> {
+        Ok(())
+    }
+
+    /// The normalized value is the average of both sides
+    fn normalized_value(
+        &self,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+    ) -> Option<PreciseNumber> {
+        let value = swap_token_a_amount
+            .checked_add(swap_token_b_amount)?
+            .checked_div(2)?;
+        PreciseNumber::new(value)
+    }
+}
+
+/// IsInitialized is required to use `Pack::pack` and `Pack::unpack`
+impl IsInitialized for IdentityCurve {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+impl Sealed for IdentityCurve {}
+impl Pack for IdentityCurve {
+    const LEN: usize = 0;
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        (self as &dyn DynPack).pack_into_slice(output);
+    }
+
+    fn unpack_from_slice(_input: &[u8]) -> Result<IdentityCurve, ProgramError> {
+        Ok(Self {})
+    }
+}
+
+impl DynPack for IdentityCurve {
+    fn pack_into_slice(&self, _output: &mut [u8]) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::curve::{
+            base::{CurveType, SwapCurve},
+            fees::Fees,
+        },
+        std::sync::Arc,
+    };
+
+    fn identity_swap_curve() -> SwapCurve {
+        SwapCurve {
+            curve_type: CurveType::Identity,
+            calculator: Arc::new(IdentityCurve {}),
+        }
+    }
+
+    #[test]
+    fn swap_is_one_to_one() {
+        let curve = IdentityCurve {};
+        for trade_direction in [TradeDirection::AtoB, TradeDirection::BtoA] {
+            let result = curve
+                .swap_without_fees(1_234, 10, 1_000_000, trade_direction)
+                .unwrap();
+            assert_eq!(result.source_amount_swapped, 1_234);
+            assert_eq!(result.destination_amount_swapped, 1_234);
+            assert_eq!(
+                curve.spot_amount_out(1_234, 10, 1_000_000, trade_direction),
+                Some(1_234)
+            );
+        }
+        assert!(curve
+            .swap_without_fees(0, 10, 10, TradeDirection::AtoB)
+            .is_none());
+    }
+
+    #[test]
+    fn swap_output_only_loses_fees() {
+        let fees = Fees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator: 5,
+            owner_trade_fee_denominator: 10_000,
+            owner_withdraw_fee_numerator: 0,
+            owner_withdraw_fee_denominator: 0,
+            host_fee_numerator: 0,
+            host_fee_denominator: 0,
+        };
+        let swap_curve = identity_swap_curve();
+        let source_amount = 100_000;
+        let result = swap_curve
+            .swap(
+                source_amount,
+                1_000_000,
+                1_000_000,
+                TradeDirection::AtoB,
+                &fees,
+            )
+            .unwrap();
+        assert_eq!(result.trade_fee, 250);
+        assert_eq!(result.owner_fee, 50);
+        assert_eq!(result.source_amount_swapped, source_amount);
+        assert_eq!(
+            result.destination_amount_swapped,
+            source_amount - result.trade_fee - result.owner_fee
+        );
+        assert_eq!(result.new_swap_source_amount, 1_100_000);
+        assert_eq!(result.new_swap_destination_amount, 900_300);
+
+        // without fees nothing is taken
+        let result = swap_curve
+            .swap(
+                source_amount,
+                1_000_000,
+                1_000_000,
+                TradeDirection::BtoA,
+                &Fees::default(),
+            )
+            .unwrap();
+        assert_eq!(result.destination_amount_swapped, source_amount);
+        assert_eq!(result.trade_fee, 0);
+        assert_eq!(result.owner_fee, 0);
+    }
+
+    #[test]
+    fn single_sided_deposit_pays_half_the_trade_fee() {
+        let fees = Fees {
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 100,
+            ..Fees::default()
+        };
+        let swap_curve = identity_swap_curve();
+        let with_fee = swap_curve
+            .deposit_single_token_type(
+                20_000,
+                1_000_000,
+                1_000_000,
+                1_000_000,
+                TradeDirection::AtoB,
+                &fees,
+            )
+            .unwrap();
+        let fee_free = swap_curve
+            .deposit_single_token_type(
+                20_000 - 100,
+                1_000_000,
+                1_000_000,
+                1_000_000,
+                TradeDirection::AtoB,
+                &Fees::default(),
+            )
+            .unwrap();
+        assert_eq!(with_fee, fee_free);
+    }
+
+    #[test]
+    fn pack_curve() {
+        let curve = IdentityCurve {};
+        let mut packed = [0u8; IdentityCurve::LEN];
+        Pack::pack_into_slice(&curve, &mut packed[..]);
+        assert_eq!(IdentityCurve::unpack(&packed).unwrap(), curve);
+    }
+}
@@ -0,0 +1,282 @@
+//! Fee and constant product math on plain integers
+//!
+//! Everything here works on `u128` amounts, so clients can quote trades
+//! without building the program's on-chain types. The program computes its
+//! fees and constant product swaps with the same functions, so a quote
+//! matches what a swap executes. Re-exported as `client` with the `client`
+//! feature; building with `--no-default-features --features client` leaves
+//! out the rest of the crate and all of its dependencies.
+
+/// Helper function for calculating swap fee
+/// 	1.	如果手续费分子或代币金额为 0，则没有手续费，返回 Some(0)。
+// 2.	使用 checked_mul 和 checked_div 来计算手续费，确保计算过程中没有溢出或除零错误。
+// 3.	如果计算出的手续费为 0，返回至少 1 个代币的手续费（作为最低手续费）。
+// 4.	如果计算出的手续费大于 0，则返回实际计算的手续费。
+pub fn calculate_fee(
+    token_amount: u128,
+    fee_numerator: u128,
+    fee_denominator: u128,
+) -> Option<u128> {
+    if fee_numerator == 0 || token_amount == 0 {
+        Some(0)
+    } else {
+        let fee = token_amount
+            .checked_mul(fee_numerator)?
+            .checked_div(fee_denominator)?;
+        if fee == 0 {
+            Some(1) // minimum fee of one token
+        } else {
+            Some(fee)
+        }
+    }
+}
+
+/// Division rounding up
+pub fn ceil_div(dividend: u128, divisor: u128) -> Option<u128> {
+    dividend
+        .checked_add(divisor)?
+        .checked_sub(1)?
+        .checked_div(divisor)
+}
+
+/// Amount that is left with `post_fee_amount` once a fee of
+/// `fee_numerator / fee_denominator` is taken from it
+pub fn pre_fee_amount(
+    post_fee_amount: u128,
+    fee_numerator: u128,
+    fee_denominator: u128,
+) -> Option<u128> {
+    if fee_numerator == 0 || fee_denominator == 0 {
+        Some(post_fee_amount)
+    } else if fee_numerator == fee_denominator || post_fee_amount == 0 {
+        Some(0)
+    } else {
+        let numerator = post_fee_amount.checked_mul(fee_denominator)?;
+        let denominator = fee_denominator.checked_sub(fee_numerator)?;
+        ceil_div(numerator, denominator)
+    }
+}
+
+/// Division that does not truncate value from either side, returning the
+/// quotient rounded up and the smallest divisor giving that quotient, as
+/// `spl_math`'s `CheckedCeilDiv` does. Fails if the divisor is larger than
+/// the dividend.
+pub fn checked_ceil_div(dividend: u128, divisor: u128) -> Option<(u128, u128)> {
+    let mut quotient = dividend.checked_div(divisor)?;
+    if quotient == 0 {
+        return None;
+    }
+    let mut divisor = divisor;
+    if dividend.checked_rem(divisor)? > 0 {
+        quotient = quotient.checked_add(1)?;
+        divisor = dividend.checked_div(quotient)?;
+        if dividend.checked_rem(quotient)? > 0 {
+            divisor = divisor.checked_add(1)?;
+        }
+    }
+    Some((quotient, divisor))
+}
+
+/// The constant product swap of `source_amount`, without fees, returning the
+/// source and destination amounts swapped. The output rounds down, and only
+/// the source needed for it is taken. `None` if nothing would come out.
+// 恒定乘积交换：新的目标储备向上取整，再反推实际需要的源代币数量
+pub fn constant_product_swap(
+    source_amount: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+) -> Option<(u128, u128)> {
+    let invariant = swap_source_amount.checked_mul(swap_destination_amount)?;
+
+    let new_swap_source_amount = swap_source_amount.checked_add(source_amount)?;
+    let (new_swap_destination_amount, new_swap_source_amount) =
+        checked_ceil_div(invariant, new_swap_source_amount)?;
+
+    let source_amount_swapped = new_swap_source_amount.checked_sub(swap_source_amount)?;
+    let destination_amount_swapped =
+        swap_destination_amount.checked_sub(new_swap_destination_amount)?;
+    if destination_amount_swapped == 0 {
+        return None;
+    }
+    Some((source_amount_swapped, destination_amount_swapped))
+}
+
+/// Trading fees of a swap, the pool's trade and owner trade fees or those
+/// overriding them for one direction. Zero over zero is no fee.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TradeFees {
+    /// Trade fee numerator
+    pub trade_fee_numerator: u64,
+    /// Trade fee denominator
+    pub trade_fee_denominator: u64,
+    /// Owner trade fee numerator
+    pub owner_trade_fee_numerator: u64,
+    /// Owner trade fee denominator
+    pub owner_trade_fee_denominator: u64,
+}
+
+impl TradeFees {
+    /// Trading fee kept by the pool for an input of `source_amount`
+    pub fn trading_fee(&self, source_amount: u128) -> Option<u128> {
+        calculate_fee(
+            source_amount,
+            u128::from(self.trade_fee_numerator),
+            u128::from(self.trade_fee_denominator),
+        )
+    }
+
+    /// Owner trading fee for an input of `source_amount`
+    pub fn owner_trading_fee(&self, source_amount: u128) -> Option<u128> {
+        calculate_fee(
+            source_amount,
+            u128::from(self.owner_trade_fee_numerator),
+            u128::from(self.owner_trade_fee_denominator),
+        )
+    }
+}
+
+/// Outcome of a constant product swap, as the program executes it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quote {
+    /// Source amount taken from the trader, fees included
+    pub source_amount_swapped: u128,
+    /// Destination amount given to the trader
+    pub destination_amount_swapped: u128,
+    /// Source amount kept by the pool as trading fee
+    pub trade_fee: u128,
+    /// Source amount kept by the pool as owner fee, for which pool tokens
+    /// are minted
+    pub owner_fee: u128,
+}
+
+/// Quotes a swap of `source_amount` on a constant product pool: the fees are
+/// taken from the input first and the rest is swapped along the curve.
+/// Token-2022 transfer fees and fee discounts are not applied.
+pub fn quote_constant_product(
+    source_amount: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    trade_fees: &TradeFees,
+) -> Option<Quote> {
+    let trade_fee = trade_fees.trading_fee(source_amount)?;
+    let owner_fee = trade_fees.owner_trading_fee(source_amount)?;
+    let total_fees = trade_fee.checked_add(owner_fee)?;
+    let source_amount_less_fees = source_amount.checked_sub(total_fees)?;
+
+    let (source_amount_swapped, destination_amount_swapped) = constant_product_swap(
+        source_amount_less_fees,
+        swap_source_amount,
+        swap_destination_amount,
+    )?;
+    Some(Quote {
+        source_amount_swapped: source_amount_swapped.checked_add(total_fees)?,
+        destination_amount_swapped,
+        trade_fee,
+        owner_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "program")]
+    use {
+        crate::curve::{
+            base::{CurveType, SwapCurve},
+            calculator::TradeDirection,
+            constant_product::ConstantProductCurve,
+            fees::Fees,
+        },
+        proptest::prelude::*,
+        spl_math::checked_ceil_div::CheckedCeilDiv,
+        std::sync::Arc,
+    };
+
+    #[test]
+    fn quote_constant_product_takes_fees_first() {
+        let trade_fees = TradeFees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator: 5,
+            owner_trade_fee_denominator: 10_000,
+        };
+        assert_eq!(
+            quote_constant_product(1_000, 1_000_000, 5_000_000, &trade_fees),
+            Some(Quote {
+                source_amount_swapped: 1_000,
+                destination_amount_swapped: 4_980,
+                trade_fee: 2,
+                owner_fee: 1,
+            })
+        );
+        assert_eq!(
+            quote_constant_product(1, 1_000_000, 5_000_000, &trade_fees),
+            None
+        );
+    }
+
+    #[cfg(feature = "program")]
+    #[test]
+    fn ceil_div_matches_spl_math() {
+        for (dividend, divisor) in [(400, 32), (50_000_000, 1_100), (1_000, 1_000), (1, 1_000)] {
+            assert_eq!(
+                checked_ceil_div(dividend, divisor),
+                dividend.checked_ceil_div(divisor)
+            );
+        }
+        assert_eq!(checked_ceil_div(400, 32), Some((13, 31)));
+        assert_eq!(checked_ceil_div(1, 0), None);
+    }
+
+    #[cfg(feature = "program")]
+    proptest! {
+        #[test]
+        fn quote_matches_swap_curve(
+            source_amount in 1..u64::MAX,
+            swap_source_amount in 1..u64::MAX,
+            swap_destination_amount in 1..u64::MAX,
+            trade_fee_numerator in 0..100u64,
+            owner_trade_fee_numerator in 0..100u64,
+        ) {
+            let fees = Fees {
+                trade_fee_numerator,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator,
+                owner_trade_fee_denominator: 10_000,
+                ..Fees::default()
+            };
+            let swap_curve = SwapCurve {
+                curve_type: CurveType::ConstantProduct,
+                calculator: Arc::new(ConstantProductCurve {}),
+            };
+            let on_chain = swap_curve.swap(
+                u128::from(source_amount),
+                u128::from(swap_source_amount),
+                u128::from(swap_destination_amount),
+                TradeDirection::AtoB,
+                &fees,
+            );
+            let quote = quote_constant_product(
+                u128::from(source_amount),
+                u128::from(swap_source_amount),
+                u128::from(swap_destination_amount),
+                &fees.trade_fees(),
+            );
+            match (on_chain, quote) {
+                (Some(on_chain), Some(quote)) => {
+                    prop_assert_eq!(quote.source_amount_swapped, on_chain.source_amount_swapped);
+                    prop_assert_eq!(
+                        quote.destination_amount_swapped,
+                        on_chain.destination_amount_swapped
+                    );
+                    prop_assert_eq!(quote.trade_fee, on_chain.trade_fee);
+                    prop_assert_eq!(quote.owner_fee, on_chain.owner_fee);
+                }
+                (None, None) => {}
+                (on_chain, quote) => {
+                    prop_assert!(false, "on chain {:?}, quote {:?}", on_chain, quote)
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,17 @@
 //! Curve invariant implementations
 
+#[cfg(feature = "program")]
 pub mod base;
+#[cfg(feature = "program")]
 pub mod calculator;
+#[cfg(feature = "program")]
 pub mod constant_price;
+#[cfg(feature = "program")]
 pub mod constant_product;
+#[cfg(feature = "program")]
 pub mod fees;
+#[cfg(feature = "test-curve")]
+pub mod identity;
+pub mod math;
+#[cfg(feature = "program")]
 pub mod offset;
@@ -4,8 +4,8 @@ use {
     crate::{
         curve::{
             calculator::{
-                CurveCalculator, DynPack, RoundDirection, SwapWithoutFeesResult, TradeDirection,
-                TradingTokenResult,
+                map_zero_to_none, CurveCalculator, DynPack, RoundDirection, SwapWithoutFeesResult,
+                TradeDirection, TradingTokenResult,
             },
             constant_product::{
                 deposit_single_token_type, normalized_value, pool_tokens_to_trading_tokens, swap,
@@ -16,6 +16,7 @@ use {
     },
     arrayref::{array_mut_ref, array_ref},
     solana_program::{
+        msg,
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack, Sealed},
     },
@@ -67,6 +68,34 @@ impl CurveCalculator for OffsetCurve {
         swap(source_amount, swap_source_amount, swap_destination_amount)
     }
 
+    /// The spot price for the offset curve includes the offset on the
+    /// token B side
+    fn spot_amount_out(
+        &self,
+        source_amount: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        let token_b_offset = self.token_b_offset as u128;
+        let (swap_source_amount, swap_destination_amount) = match trade_direction {
+            TradeDirection::AtoB => (
+                swap_source_amount,
+                swap_destination_amount.checked_add(token_b_offset)?,
+            ),
+            TradeDirection::BtoA => (
+                swap_source_amount.checked_add(token_b_offset)?,
+                swap_destination_amount,
+            ),
+        };
+        // token B 的实际储备可以为零（由偏移量补足），但 token A 储备为零时没有现货价格
+        let swap_source_amount = map_zero_to_none(swap_source_amount)?;
+        let swap_destination_amount = map_zero_to_none(swap_destination_amount)?;
+        source_amount
+            .checked_mul(swap_destination_amount)?
+            .checked_div(swap_source_amount)
+    }
+
     /// The conversion for the offset curve needs to take into account the
     /// offset
     fn pool_tokens_to_trading_tokens(
@@ -87,6 +116,14 @@ impl CurveCalculator for OffsetCurve {
         )
     }
 
+    /// The deposit ratio includes the offset on the token B side
+    fn deposit_ratio(&self, reserve_a: u128, reserve_b: u128) -> (u128, u128) {
+        (
+            reserve_a,
+            reserve_b.saturating_add(u128::from(self.token_b_offset)),
+        )
+    }
+
     /// Get the amount of pool tokens for the given amount of token A and B,
     /// taking into account the offset
     fn deposit_single_token_type(
@@ -128,14 +165,25 @@ impl CurveCalculator for OffsetCurve {
         )
     }
 
+    /// The invariant is the product of the reserves, with the offset added to
+    /// token B
+    fn invariant(&self, reserve_a: u128, reserve_b: u128) -> Option<u128> {
+        reserve_b
+            .checked_add(u128::from(self.token_b_offset))?
+            .checked_mul(reserve_a)
+    }
+
     fn validate(&self) -> Result<(), SwapError> {
         if self.token_b_offset == 0 {
-            Err(SwapError::InvalidCurve)
+            msg!("Offset curve: token B offset must be non-zero");
+            Err(SwapError::CurveValidationFailed)
         } else {
             Ok(())
         }
     }
 
+    /// The offset provides virtual token B liquidity, so the pool may start
+    /// without any real token B, but it still needs token A
     fn validate_supply(&self, token_a_amount: u64, _token_b_amount: u64) -> Result<(), SwapError> {
         if token_a_amount == 0 {
             return Err(SwapError::EmptySupply);
@@ -227,6 +275,25 @@ mod tests {
         assert_eq!(curve, unpacked);
     }
 
+    #[test]
+    fn validate_offset() {
+        let curve = OffsetCurve { token_b_offset: 0 };
+        assert_eq!(curve.validate(), Err(SwapError::CurveValidationFailed));
+        let curve = OffsetCurve { token_b_offset: 1 };
+        assert_eq!(curve.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_supply_without_token_b() {
+        let curve = OffsetCurve {
+            token_b_offset: 1_000_000,
+        };
+        assert_eq!(curve.validate_supply(1_000, 0), Ok(()));
+        assert_eq!(curve.validate_supply(1_000, 1_000), Ok(()));
+        assert_eq!(curve.validate_supply(0, 1_000), Err(SwapError::EmptySupply));
+        assert_eq!(curve.validate_supply(0, 0), Err(SwapError::EmptySupply));
+    }
+
     #[test]
     fn swap_no_offset() {
         let swap_source_amount: u128 = 1_000;
@@ -282,6 +349,97 @@ mod tests {
         assert!(bad_result.is_none());
     }
 
+    #[test]
+    fn spot_amount_out_offset() {
+        let swap_source_amount: u128 = 1_000_000;
+        let swap_destination_amount: u128 = 0;
+        let source_amount: u128 = 100;
+        let token_b_offset = 1_000_000;
+        let curve = OffsetCurve { token_b_offset };
+        // the swap itself loses one token to price impact, the spot price does not
+        let spot = curve
+            .spot_amount_out(
+                source_amount,
+                swap_source_amount,
+                swap_destination_amount,
+                TradeDirection::AtoB,
+            )
+            .unwrap();
+        assert_eq!(spot, source_amount);
+        let spot = curve
+            .spot_amount_out(
+                source_amount,
+                swap_destination_amount,
+                swap_source_amount,
+                TradeDirection::BtoA,
+            )
+            .unwrap();
+        assert_eq!(spot, source_amount);
+    }
+
+    #[test]
+    fn invariant_includes_offset() {
+        let curve = OffsetCurve {
+            token_b_offset: 2_000,
+        };
+        assert_eq!(curve.invariant(1_000, 0), Some(2_000_000));
+        assert_eq!(curve.invariant(1_000, 500), Some(2_500_000));
+        let results = curve
+            .swap_without_fees(100, 0, 1_000, TradeDirection::BtoA)
+            .unwrap();
+        let new_k = curve
+            .invariant(
+                1_000 - results.destination_amount_swapped,
+                results.source_amount_swapped,
+            )
+            .unwrap();
+        assert!(new_k >= 2_000_000);
+    }
+
+    #[test]
+    fn deposit_ratio_offset() {
+        let token_b_offset = 2_000;
+        let curve = OffsetCurve { token_b_offset };
+        let (ratio_a, ratio_b) = curve.deposit_ratio(1_000, 0);
+        assert_eq!((ratio_a, ratio_b), (1_000, 2_000));
+        let results = curve
+            .pool_tokens_to_trading_tokens(10, 100, 1_000, 0, RoundDirection::Floor)
+            .unwrap();
+        assert_eq!(
+            results.token_a_amount * ratio_b,
+            results.token_b_amount * ratio_a
+        );
+    }
+
+    #[test]
+    fn spot_amount_out_zero_token_a() {
+        let swap_token_a_amount: u128 = 0;
+        let swap_token_b_amount: u128 = 0;
+        let source_amount: u128 = 100;
+        let curve = OffsetCurve {
+            token_b_offset: 1_000_000,
+        };
+        // the offset stands in for token B, but nothing stands in for token A
+        assert_eq!(
+            curve.spot_amount_out(
+                source_amount,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                TradeDirection::AtoB,
+            ),
+            None
+        );
+        assert_eq!(
+            curve.spot_amount_out(
+                source_amount,
+                swap_token_b_amount,
+                swap_token_a_amount,
+                TradeDirection::BtoA,
+            ),
+            None
+        );
+    }
+
     #[test]
     fn swap_a_to_b_max_offset() {
         let swap_source_amount: u128 = 10_000_000;
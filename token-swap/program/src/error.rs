@@ -117,6 +117,103 @@ pub enum SwapError {
     /// The pool fee account is invalid.
     #[error("The pool fee account is invalid")]
     InvalidFeeAccount,
+    /// The destination account is not owned by the requested recipient.
+    #[error("The destination account is not owned by the requested recipient")]
+    IncorrectRecipient,
+
+    // 30.
+    /// The pool token mint has fewer decimals than the program allows.
+    #[error("The pool token mint has too few decimals")]
+    InvalidPoolMintDecimals,
+    /// The native mint cannot be used as the pool token mint.
+    #[error("The native mint cannot be used as the pool token mint")]
+    NativePoolMint,
+    /// The swap token account balances after a swap do not match the curve.
+    #[error("The swap token account balances do not match the expected reserves")]
+    ReserveMismatch,
+    /// The governance token account is for another mint or another owner.
+    #[error("The governance token account does not match the mint or the trader")]
+    InvalidGovernanceAccount,
+    /// The initial reserves imply a price outside the allowed range.
+    #[error("The initial reserves imply a price outside the allowed range")]
+    PriceOutOfRange,
+
+    // 35.
+    /// The swap would move the price by more than the allowed impact.
+    #[error("The swap would move the price by more than the allowed impact")]
+    PriceImpactExceeded,
+    /// Pool tokens were deposited too recently to be withdrawn.
+    #[error("Pool tokens cannot be withdrawn until the withdraw cooldown has passed")]
+    WithdrawCooldown,
+    /// The swap does not track its reserve balances.
+    #[error("The swap does not track its reserve balances")]
+    ReservesNotTracked,
+    /// The swap takes in less than the pool's minimum trade amount.
+    #[error("The swap amount is below the pool's minimum trade amount")]
+    TradeTooSmall,
+    /// The curve's initial pool token supply does not fit in a token amount.
+    #[error("The initial pool token supply is too large to mint")]
+    InitialSupplyTooLarge,
+
+    // 40.
+    /// The pool token mint has an extension the swap does not support.
+    #[error("The pool token mint has an unsupported extension")]
+    InvalidPoolMintExtension,
+    /// A token account's owner can be changed, but the constraints require
+    /// the `ImmutableOwner` extension.
+    #[error("The token account does not have an immutable owner")]
+    MutableAccountOwner,
+    /// The account to unwrap after a withdrawal does not hold wrapped SOL.
+    #[error("The withdrawal destination does not hold wrapped SOL")]
+    NotWrappedSol,
+    /// The swap curve's parameters are invalid; the program log says which
+    /// curve and why.
+    #[error("The swap curve's parameters failed validation")]
+    CurveValidationFailed,
+    /// The pool tokens minted as the owner fee exceed the trader's limit.
+    #[error("The owner fee exceeds the swap's limit")]
+    OwnerFeeLimitExceeded,
+
+    // 45.
+    /// The pool token supply differs from the pool tokens the swap has
+    /// minted and burned, e.g. because someone else minted them.
+    #[error("The pool token supply does not match the swap's accounting")]
+    PoolSupplyMismatch,
+    /// The swap does not track the pool tokens it mints and burns.
+    #[error("The swap does not track its pool token supply")]
+    PoolSupplyNotTracked,
+    /// Token A or B's mint has an extension the swap cannot price, such as
+    /// confidential transfers hiding the reserve balances.
+    #[error("The token mint has an extension the swap does not support")]
+    UnsupportedMintExtension,
+    /// A reserve holds less than the pool's minimum for swaps.
+    #[error("The pool's reserves are below the minimum liquidity for swaps")]
+    InsufficientLiquidity,
+    /// Tokens can only be recovered from accounts other than the pool's
+    /// reserves and pool fee account.
+    #[error("The pool's reserve and fee accounts cannot be recovered from")]
+    UnrecoverableAccount,
+
+    // 50.
+    /// The user's destination account has token-2022's `CpiGuard` enabled,
+    /// or the source account has it enabled and its owner signs the swap.
+    #[error("CPI Guard is enabled on the user's token account")]
+    CpiGuardEnabled,
+    /// The pool only lets its allowed caller authorize swaps.
+    #[error("The swap authority is not allowed to swap in this pool")]
+    Unauthorized,
+    /// A deposit cannot be passed on to the swap program itself.
+    #[error("The deposit cannot be passed on to the swap program itself")]
+    InvalidCpiTarget,
+}
+impl SwapError {
+    /// Decodes the code carried by `ProgramError::Custom` back into the
+    /// error, without needing `FromPrimitive` in scope. Codes are the
+    /// variant's position in the enum and never change once released; the
+    /// `Display` impl gives the matching message.
+    pub fn from_u32(code: u32) -> Option<Self> {
+        <Self as num_traits::FromPrimitive>::from_u32(code)
+    }
 }
 impl From<SwapError> for ProgramError {
     fn from(e: SwapError) -> Self {
@@ -200,6 +297,103 @@ impl PrintProgramError for SwapError {
             SwapError::InvalidFeeAccount => {
                 msg!("Error: The pool fee account is invalid")
             }
+            SwapError::IncorrectRecipient => {
+                msg!("Error: The destination account is not owned by the requested recipient")
+            }
+            SwapError::InvalidPoolMintDecimals => {
+                msg!("Error: The pool token mint has too few decimals")
+            }
+            SwapError::NativePoolMint => {
+                msg!("Error: The native mint cannot be used as the pool token mint")
+            }
+            SwapError::ReserveMismatch => {
+                msg!("Error: The swap token account balances do not match the expected reserves")
+            }
+            SwapError::InvalidGovernanceAccount => {
+                msg!("Error: The governance token account does not match the mint or the trader")
+            }
+            SwapError::PriceOutOfRange => {
+                msg!("Error: The initial reserves imply a price outside the allowed range")
+            }
+            SwapError::PriceImpactExceeded => {
+                msg!("Error: The swap would move the price by more than the allowed impact")
+            }
+            SwapError::WithdrawCooldown => {
+                msg!(
+                    "Error: Pool tokens cannot be withdrawn until the withdraw cooldown has passed"
+                )
+            }
+            SwapError::ReservesNotTracked => {
+                msg!("Error: The swap does not track its reserve balances")
+            }
+            SwapError::TradeTooSmall => {
+                msg!("Error: The swap amount is below the pool's minimum trade amount")
+            }
+            SwapError::InitialSupplyTooLarge => {
+                msg!("Error: The initial pool token supply is too large to mint")
+            }
+            SwapError::InvalidPoolMintExtension => {
+                msg!("Error: The pool token mint has an unsupported extension")
+            }
+            SwapError::MutableAccountOwner => {
+                msg!("Error: The token account does not have an immutable owner")
+            }
+            SwapError::NotWrappedSol => {
+                msg!("Error: The withdrawal destination does not hold wrapped SOL")
+            }
+            SwapError::CurveValidationFailed => {
+                msg!("Error: The swap curve's parameters failed validation")
+            }
+            SwapError::OwnerFeeLimitExceeded => {
+                msg!("Error: The owner fee exceeds the swap's limit")
+            }
+            SwapError::PoolSupplyMismatch => {
+                msg!("Error: The pool token supply does not match the swap's accounting")
+            }
+            SwapError::PoolSupplyNotTracked => {
+                msg!("Error: The swap does not track its pool token supply")
+            }
+            SwapError::UnsupportedMintExtension => {
+                msg!("Error: The token mint has an extension the swap does not support")
+            }
+            SwapError::InsufficientLiquidity => {
+                msg!("Error: The pool's reserves are below the minimum liquidity for swaps")
+            }
+            SwapError::UnrecoverableAccount => {
+                msg!("Error: The pool's reserve and fee accounts cannot be recovered from")
+            }
+            SwapError::CpiGuardEnabled => {
+                msg!("Error: CPI Guard is enabled on the user's token account")
+            }
+            SwapError::Unauthorized => {
+                msg!("Error: The swap authority is not allowed to swap in this pool")
+            }
+            SwapError::InvalidCpiTarget => {
+                msg!("Error: The deposit cannot be passed on to the swap program itself")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_round_trip() {
+        let mut code = 0;
+        while let Some(error) = SwapError::from_u32(code) {
+            assert_eq!(error.clone() as u32, code);
+            assert_eq!(
+                ProgramError::from(error.clone()),
+                ProgramError::Custom(code)
+            );
+            assert!(!error.to_string().is_empty());
+            code += 1;
         }
+        // every variant is reachable and codes are contiguous
+        assert_eq!(code, SwapError::InvalidCpiTarget as u32 + 1);
+        assert_eq!(SwapError::from_u32(0), Some(SwapError::AlreadyInUse));
+        assert_eq!(SwapError::from_u32(29), Some(SwapError::IncorrectRecipient));
     }
 }
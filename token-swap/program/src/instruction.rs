@@ -6,16 +6,23 @@
 use arbitrary::Arbitrary;
 use {
     crate::{
-        curve::{base::SwapCurve, fees::Fees},
+        curve::{base::SwapCurve, calculator::TradeDirection, fees::Fees},
         error::SwapError,
+        state::{
+            DirectionalFees, FeeDiscount, FeeMode, FeeSweep, HostFeeBase, HostFeeSplit, LaunchFee,
+        },
     },
     solana_program::{
         instruction::{AccountMeta, Instruction},
         program_error::ProgramError,
         program_pack::Pack,
         pubkey::Pubkey,
+        system_program, sysvar,
+    },
+    std::{
+        convert::{TryFrom, TryInto},
+        mem::size_of,
     },
-    std::{convert::TryInto, mem::size_of},
 };
 
 /// Initialize instruction data  初始化指令数据
@@ -27,6 +34,83 @@ pub struct Initialize {
     /// swap curve info for pool, including CurveType and anything
     /// else that may be required
     pub swap_curve: SwapCurve,
+    /// How owner trading fees are collected. Optional on the wire, defaults
+    /// to minting pool tokens.
+    pub fee_mode: FeeMode,
+    /// Trading fee discounts for governance token holders. Optional on the
+    /// wire, defaults to no discounts.
+    pub fee_discount: FeeDiscount,
+    /// Weights for splitting the host fee across several host fee accounts.
+    /// Optional on the wire, defaults to a single host fee account.
+    pub host_fee_split: HostFeeSplit,
+    /// Seconds after a deposit before the depositor may withdraw. Optional
+    /// on the wire, defaults to no cooldown.
+    pub withdraw_cooldown_secs: u64,
+    /// Whether swaps pay host fees. Optional on the wire, defaults to
+    /// enabled.
+    pub host_fees_enabled: bool,
+    /// Cap on the pool tokens minted as owner fee by a single swap, in basis
+    /// points of the pool token supply, zero for no cap. Optional on the
+    /// wire, defaults to no cap.
+    pub max_owner_fee_bps: u16,
+    /// Smallest amount a swap may take in, if any. Optional on the wire,
+    /// defaults to no minimum.
+    pub min_trade_amount: Option<u64>,
+    /// Whether the pool token mint may charge a transfer fee. Optional on
+    /// the wire, defaults to rejecting such mints.
+    pub allow_pool_mint_transfer_fee: bool,
+    /// Sweeping of owner fees beyond a pool fee account balance. Optional on
+    /// the wire, defaults to no sweeping.
+    pub fee_sweep: FeeSweep,
+    /// Smallest balance both reserves must hold for swaps to be allowed, if
+    /// any. Optional on the wire, defaults to no floor.
+    pub min_reserve_for_swap: Option<u64>,
+    /// What the host fee fraction is taken of. Optional on the wire,
+    /// defaults to the owner fee.
+    pub host_fee_base: HostFeeBase,
+    /// Trading fees overridden per swap direction. Optional on the wire,
+    /// defaults to the same fees both ways.
+    pub directional_fees: DirectionalFees,
+    /// Trade fee decaying from launch to a floor, replacing the pool's trade
+    /// fee. A launch time of zero starts the decay when the pool is
+    /// initialized. Optional on the wire, defaults to no launch fee.
+    pub launch_fee: LaunchFee,
+    /// Only key allowed to authorize swaps, for permissioned pools. Optional
+    /// on the wire, defaults to anyone.
+    pub allowed_caller: Option<Pubkey>,
+    /// Whether single-sided deposits are rejected, so that deposits cannot
+    /// move the pool off its ratio. Optional on the wire, defaults to
+    /// allowing them.
+    pub balanced_deposits_only: bool,
+    /// Key allowed to approve owner-gated instructions, taking precedence
+    /// over the program config and the owner of the pool fee account.
+    /// Optional on the wire, defaults to none.
+    pub owner: Option<Pubkey>,
+}
+
+impl Default for Initialize {
+    fn default() -> Self {
+        Self {
+            fees: Fees::default(),
+            swap_curve: SwapCurve::default(),
+            fee_mode: FeeMode::default(),
+            fee_discount: FeeDiscount::default(),
+            host_fee_split: HostFeeSplit::default(),
+            withdraw_cooldown_secs: 0,
+            host_fees_enabled: true,
+            max_owner_fee_bps: 0,
+            min_trade_amount: None,
+            allow_pool_mint_transfer_fee: false,
+            fee_sweep: FeeSweep::default(),
+            min_reserve_for_swap: None,
+            host_fee_base: HostFeeBase::default(),
+            directional_fees: DirectionalFees::default(),
+            launch_fee: LaunchFee::default(),
+            allowed_caller: None,
+            balanced_deposits_only: false,
+            owner: None,
+        }
+    }
 }
 
 /// Swap instruction data
@@ -40,6 +124,59 @@ pub struct Swap {
     /// Minimum amount of DESTINATION token to output, prevents excessive
     /// slippage
     pub minimum_amount_out: u64,
+    /// Optional owner the DESTINATION account must have. Lets a relayer
+    /// submit the swap on behalf of a trader without being able to redirect
+    /// the output to an account it controls.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub recipient: Option<Pubkey>,
+    /// Optional slippage tolerance in basis points, applied to
+    /// `quoted_amount_out`. The output must reach the quote less this
+    /// tolerance, so a price moved between quoting and executing the trade
+    /// counts against it. `minimum_amount_out` still applies as well.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub slippage_bps: Option<u16>,
+    /// Optional limit, in basis points, on how far the trade may move the
+    /// price away from the current spot price, trading fees aside.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub max_price_impact_bps: Option<u16>,
+    /// When the full `amount_in` would exceed `max_price_impact_bps`, swap
+    /// the largest amount that stays within it instead of failing, leaving
+    /// the rest in the SOURCE account, as reported in the return data. The
+    /// minimum output is scaled down by the share of `amount_in` filled, and
+    /// the pool's minimum trade amount applies to the filled amount.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub allow_partial: bool,
+    /// Optional limit on the pool tokens minted as the owner fee for this
+    /// trade, which can be unexpectedly large when the pool is imbalanced.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub max_owner_fee_pool_tokens: Option<u64>,
+    /// When the owner fee would exceed `max_owner_fee_pool_tokens`, mint
+    /// only that many pool tokens instead of failing.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub clamp_owner_fee: bool,
+    /// Amount of DESTINATION token the received amount may fall short of
+    /// the minimum by when a transfer fee is withheld on the DESTINATION
+    /// transfer, to absorb the fee's rounding. Ignored otherwise.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub slippage_grace: u64,
+    /// DESTINATION amount the client quoted for this trade, required with
+    /// `slippage_bps` and only allowed with it
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub quoted_amount_out: Option<u64>,
+    /// Optional memo, logged through the SPL Memo program and signed by the
+    /// user transfer authority before any transfer. Empty for no memo.
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub memo: Vec<u8>,
+}
+
+/// InitializeWithMint instruction data
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct InitializeWithMint {
+    /// Number of decimals of the pool token mint to create
+    pub decimals: u8,
+    /// Pool settings, as for `Initialize`
+    pub initialize: Initialize,
 }
 
 /// DepositAllTokenTypes instruction data
@@ -50,10 +187,40 @@ pub struct DepositAllTokenTypes {
     /// Pool token amount to transfer. token_a and token_b amount are set by
     /// the current exchange rate and size of the pool
     pub pool_token_amount: u64,
+    /// Maximum token A amount to deposit, prevents excessive slippage. A
+    /// maximum of 0 fails with `ZeroTradingTokens` if any token A is needed
+    pub maximum_token_a_amount: u64,
+    /// Maximum token B amount to deposit, prevents excessive slippage. A
+    /// maximum of 0 fails with `ZeroTradingTokens` if any token B is needed
+    pub maximum_token_b_amount: u64,
+}
+
+/// DepositAndCpi instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositAndCpi {
+    /// Pool token amount to mint, as for `DepositAllTokenTypes`
+    pub pool_token_amount: u64,
     /// Maximum token A amount to deposit, prevents excessive slippage
     pub maximum_token_a_amount: u64,
     /// Maximum token B amount to deposit, prevents excessive slippage
     pub maximum_token_b_amount: u64,
+    /// Instruction data passed as is to the target program
+    pub cpi_data: Vec<u8>,
+}
+
+/// DepositAllProportional instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositAllProportional {
+    /// Maximum token A amount to deposit
+    pub maximum_token_a_amount: u64,
+    /// Maximum token B amount to deposit
+    pub maximum_token_b_amount: u64,
+    /// Minimum amount of pool tokens to receive, prevents excessive slippage
+    pub minimum_pool_token_amount: u64,
 }
 
 /// WithdrawAllTokenTypes instruction data
@@ -68,6 +235,54 @@ pub struct WithdrawAllTokenTypes {
     pub minimum_token_a_amount: u64,
     /// Minimum amount of token B to receive, prevents excessive slippage
     pub minimum_token_b_amount: u64,
+    /// Close the wrapped SOL destination account after the withdrawal,
+    /// sending its lamports to the user's system account
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub unwrap_sol: bool,
+}
+
+/// EmergencyWithdrawProportional instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmergencyWithdrawProportional {
+    /// Amount of pool tokens to burn. User receives the same share of each
+    /// reserve as the share of the pool token supply burned.
+    pub pool_token_amount: u64,
+    /// Minimum amount of token A to receive, prevents excessive slippage
+    pub minimum_token_a_amount: u64,
+    /// Minimum amount of token B to receive, prevents excessive slippage
+    pub minimum_token_b_amount: u64,
+}
+
+/// RedeemOwnerFees instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeemOwnerFees {
+    /// Amount of pool tokens to burn from the pool fee account. The owner
+    /// receives the reserves they are worth, as for `WithdrawAllTokenTypes`.
+    pub pool_token_amount: u64,
+    /// Minimum amount of token A to receive, prevents excessive slippage
+    pub minimum_token_a_amount: u64,
+    /// Minimum amount of token B to receive, prevents excessive slippage
+    pub minimum_token_b_amount: u64,
+}
+
+/// SwapSplit instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapSplit {
+    /// SOURCE amount to transfer, output to both DESTINATION accounts is
+    /// based on the exchange rate
+    pub amount_in: u64,
+    /// Minimum combined amount of DESTINATION token to output, prevents
+    /// excessive slippage
+    pub minimum_amount_out: u64,
+    /// Share of the output, in basis points, sent to the first DESTINATION
+    /// account. The second one receives the rest.
+    pub first_destination_bps: u16,
 }
 
 /// Deposit one token type, exact amount in instruction data
@@ -82,6 +297,59 @@ pub struct DepositSingleTokenTypeExactAmountIn {
     pub minimum_pool_token_amount: u64,
 }
 
+/// DepositSingleAutoBalance instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepositSingleAutoBalance {
+    /// Maximum token amount to deposit, about half of which is swapped
+    pub source_token_amount: u64,
+    /// Minimum amount of pool tokens to receive, prevents excessive slippage
+    pub minimum_pool_token_amount: u64,
+    /// Most the internal swap may move the price, in basis points, so that
+    /// it cannot be sandwiched. Optional on the wire, defaults to no cap.
+    pub max_internal_impact_bps: Option<u16>,
+}
+
+/// GetImpliedPriceDeviation instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GetImpliedPriceDeviation {
+    /// External price of token A in token B, scaled by
+    /// [`PRICE_SCALE`](../curve/calculator/constant.PRICE_SCALE.html)
+    pub reference_price: u128,
+}
+
+/// QuoteFees instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteFees {
+    /// SOURCE amount to quote, including any transfer fee
+    pub amount_in: u64,
+    /// Which token is the SOURCE
+    pub trade_direction: TradeDirection,
+}
+
+/// QuoteWithdrawAll instruction data
+#[cfg_attr(feature = "fuzz", derive(Arbitrary))]
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteWithdrawAll {
+    /// Amount of pool tokens to quote, including the withdraw fee
+    pub pool_token_amount: u64,
+}
+
+/// SetConfig instruction data
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetConfig {
+    /// Key to approve owner-gated instructions on swaps that don't set their
+    /// own owner
+    pub owner: Pubkey,
+}
+
 /// WithdrawSingleTokenTypeExactAmountOut instruction data
 #[cfg_attr(feature = "fuzz", derive(Arbitrary))]
 #[repr(C)]
@@ -92,6 +360,10 @@ pub struct WithdrawSingleTokenTypeExactAmountOut {
     /// Maximum amount of pool tokens to burn. User receives an output of token
     /// A or B based on the percentage of the pool tokens that are returned.
     pub maximum_pool_token_amount: u64,
+    /// Close the destination account after the withdrawal if it holds
+    /// wrapped SOL, sending its lamports to the user's system account
+    #[cfg_attr(feature = "fuzz", arbitrary(default))]
+    pub unwrap_sol: bool,
 }
 
 /// Instructions supported by the token swap program.
@@ -112,13 +384,24 @@ pub enum SwapInstruction {
     ///   6. `[writable]` Pool Token Account to deposit the initial pool token
     ///      supply. Must be empty, not owned by swap authority.
     ///   7. `[]` Pool Token program id
+    ///   8. `[]` Token A mint, optional. Mints with confidential transfers
+    ///      are rejected, since their reserves cannot be priced.
+    ///   9. `[]` Token B mint, optional, checked like token A's.
+    ///   10. `[writable, signer]` Payer of the creation fee, only if the
+    ///       program's constraints charge one, which also requires 8 and 9.
+    ///   11. `[writable]` Treasury receiving the creation fee, only if charged
+    ///   12. `[]` System program, only if a creation fee is charged
     Initialize(Initialize),
 
-    ///   Swap the tokens in the pool.
+    ///   Swap the tokens in the pool. Sets the return data to the amount
+    ///   taken from the SOURCE account (u64) and the price impact of the
+    ///   trade in basis points (u16), i.e. how far the output falls short of
+    ///   the spot value of the amount swapped, trading fees aside, both
+    ///   little endian.
     ///
-    ///   0. `[]` Token-swap
+    ///   0. `[writable]` Token-swap
     ///   1. `[]` swap authority
-    ///   2. `[]` user transfer authority
+    ///   2. `[]` user transfer authority, the pool's allowed caller if it has one
     ///   3. `[writable]` token_(A|B) SOURCE Account, amount is transferable by
     ///      user transfer authority,
     ///   4. `[writable]` token_(A|B) Base Account to swap INTO.  Must be the
@@ -126,7 +409,7 @@ pub enum SwapInstruction {
     ///   5. `[writable]` token_(A|B) Base Account to swap FROM.  Must be the
     ///      DESTINATION token.
     ///   6. `[writable]` token_(A|B) DESTINATION Account assigned to USER as
-    ///      the owner.
+    ///      the owner. Must be owned by `recipient`, if provided.
     ///   7. `[writable]` Pool token mint, to generate trading fees
     ///   8. `[writable]` Fee account, to receive trading fees
     ///   9. `[]` Token (A|B) SOURCE mint
@@ -135,14 +418,27 @@ pub enum SwapInstruction {
     ///   12. `[]` Token (A|B) DESTINATION program id
     ///   13. `[]` Pool Token program id
     ///   14. `[optional, writable]` Host fee account to receive additional
-    ///       trading fees
+    ///       trading fees. If the swap splits the host fee, one account per
+    ///       configured weight, in order, all or none. Ignored if the swap
+    ///       has host fees disabled.
+    ///   15. `[optional]` Governance token account owned by the owner of the
+    ///       SOURCE account, for a trading fee discount. Requires the host fee
+    ///       account(s), and follows the last of them.
+    ///   16. `[optional, writable]` Fee sweep destination, to receive the
+    ///       owner fee beyond the swap's fee sweep threshold. Requires the
+    ///       host fee and governance accounts, and follows them; without it
+    ///       the whole owner fee goes to the fee account.
+    ///   17. `[]` SPL Memo program id, last of all, if and only if a `memo`
+    ///       is given.
+    ///
+    ///   Any account beyond the optional ones above is rejected.
     Swap(Swap),
 
     ///   Deposit both types of tokens into the pool.  The output is a "pool"
     ///   token representing ownership in the pool. Inputs are converted to
     ///   the current ratio.
     ///
-    ///   0. `[]` Token-swap
+    ///   0. `[writable]` Token-swap
     ///   1. `[]` swap authority
     ///   2. `[]` user transfer authority
     ///   3. `[writable]` token_a user transfer authority can transfer amount,
@@ -157,13 +453,27 @@ pub enum SwapInstruction {
     ///   11. `[]` Token A program id
     ///   12. `[]` Token B program id
     ///   13. `[]` Pool Token program id
+    ///   14. `[optional, writable]` Deposit record of the owner of the
+    ///       pool account, derived with `Processor::find_deposit_record_address`.
+    ///       Required if the swap has a withdraw cooldown, in which case the
+    ///       owner of the pool account must sign, as the user transfer
+    ///       authority or the payer.
+    ///   15. `[optional, writable, signer]` Payer funding the deposit record
+    ///       on first deposit. Required if the swap has a withdraw cooldown.
+    ///   16. `[optional]` System program. Required if the swap has a withdraw
+    ///       cooldown.
     DepositAllTokenTypes(DepositAllTokenTypes),
 
     ///   Withdraw both types of tokens from the pool at the current ratio,
     ///   given pool tokens. The pool tokens are burned in exchange for an
-    ///   equivalent amount of token A and B.
+    ///   equivalent amount of token A and B. Sets the return data to the
+    ///   amounts of token A and B taken out of the pool, which are capped at
+    ///   the reserves' balances, followed by the pool tokens taken as the
+    ///   withdrawal fee, zero when withdrawing from the pool fee account, and
+    ///   the amounts of token A and B the user accounts receive once
+    ///   token-2022 transfer fees are withheld, each a little endian u64.
     ///
-    ///   0. `[]` Token-swap
+    ///   0. `[writable]` Token-swap
     ///   1. `[]` swap authority
     ///   2. `[]` user transfer authority
     ///   3. `[writable]` Pool mint account, swap authority is the owner
@@ -179,13 +489,22 @@ pub enum SwapInstruction {
     ///   12. `[]` Pool Token program id
     ///   13. `[]` Token A program id
     ///   14. `[]` Token B program id
+    ///   15. `[optional]` Deposit record of the owner of the SOURCE Pool
+    ///       account, derived with `Processor::find_deposit_record_address`.
+    ///       Required if the swap has a withdraw cooldown.
+    ///   16. `[optional, writable]` System account to receive the lamports of
+    ///       the wrapped SOL user Account, which is closed by the user
+    ///       transfer authority. Required if `unwrap_sol` is set; follows the
+    ///       deposit record, or takes its place if there is no cooldown.
+    ///   17. `[optional]` System program. Required if `unwrap_sol` is set.
     WithdrawAllTokenTypes(WithdrawAllTokenTypes),
 
     ///   Deposit one type of tokens into the pool. The output is a "pool"
     ///   token representing ownership into the pool. Input token is
     ///   converted as if a swap and deposit all token types were performed.
+    ///   Rejected by pools that only take balanced deposits.
     ///
-    ///   0. `[]` Token-swap
+    ///   0. `[writable]` Token-swap
     ///   1. `[]` swap authority
     ///   2. `[]` user transfer authority
     ///   3. `[writable]` token_(A|B) SOURCE Account, amount is transferable by
@@ -198,12 +517,21 @@ pub enum SwapInstruction {
     ///   8. `[]` Token (A|B) SOURCE mint
     ///   9. `[]` Token (A|B) SOURCE program id
     ///   10. `[]` Pool Token program id
+    ///   11. `[optional, writable]` Deposit record of the owner of the
+    ///       pool account, derived with `Processor::find_deposit_record_address`.
+    ///       Required if the swap has a withdraw cooldown, in which case the
+    ///       owner of the pool account must sign, as the user transfer
+    ///       authority or the payer.
+    ///   12. `[optional, writable, signer]` Payer funding the deposit record
+    ///       on first deposit. Required if the swap has a withdraw cooldown.
+    ///   13. `[optional]` System program. Required if the swap has a withdraw
+    ///       cooldown.
     DepositSingleTokenTypeExactAmountIn(DepositSingleTokenTypeExactAmountIn),
 
     ///   Withdraw one token type from the pool at the current ratio given the
     ///   exact amount out expected.
     ///
-    ///   0. `[]` Token-swap
+    ///   0. `[writable]` Token-swap
     ///   1. `[]` swap authority
     ///   2. `[]` user transfer authority
     ///   3. `[writable]` Pool mint account, swap authority is the owner
@@ -216,7 +544,268 @@ pub enum SwapInstruction {
     ///   9. `[]` Token (A|B) DESTINATION mint
     ///   10. `[]` Pool Token program id
     ///   11. `[]` Token (A|B) DESTINATION program id
+    ///   12. `[optional]` Deposit record of the owner of the SOURCE Pool
+    ///       account, derived with `Processor::find_deposit_record_address`.
+    ///       Required if the swap has a withdraw cooldown.
+    ///   13. `[optional, writable]` System account to receive the lamports of
+    ///       the wrapped SOL user Account, which is closed by the user
+    ///       transfer authority. Required if `unwrap_sol` is set; follows the
+    ///       deposit record, or takes its place if there is no cooldown.
+    ///   14. `[optional]` System program. Required if `unwrap_sol` is set.
     WithdrawSingleTokenTypeExactAmountOut(WithdrawSingleTokenTypeExactAmountOut),
+
+    ///   Transfer tokens sent directly to the swap's token accounts, beyond
+    ///   the reserves recorded by the last swap, deposit or withdrawal, to
+    ///   the pool owner, see `SetConfig`.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` swap authority
+    ///   2. `[writable]` token_a Swap Account to skim FROM.
+    ///   3. `[writable]` token_b Swap Account to skim FROM.
+    ///   4. `[writable]` token_a Account to credit, owned by the pool owner.
+    ///   5. `[writable]` token_b Account to credit, owned by the pool owner.
+    ///   6. `[]` Pool fee account
+    ///   7. `[]` Token A mint
+    ///   8. `[]` Token B mint
+    ///   9. `[]` Token A program id
+    ///   10. `[]` Token B program id
+    ///   11. `[]` Program config, see `SetConfig`
+    Skim,
+
+    ///   Deposit both types of tokens into the pool at the current ratio,
+    ///   receiving as many pool tokens as the scarcer of the two maximum
+    ///   amounts allows. Only the amounts needed for those pool tokens are
+    ///   taken, so neither side is over-charged.
+    ///
+    ///   Takes the same accounts as `DepositAllTokenTypes`.
+    DepositAllProportional(DepositAllProportional),
+
+    ///   Withdraw both types of tokens from the pool in proportion to the
+    ///   reserves, without going through the swap curve, for recovering
+    ///   liquidity if the curve can no longer compute withdrawals. Charges no
+    ///   withdrawal fee and ignores any withdraw cooldown, so it must be
+    ///   approved by the pool owner, see `SetConfig`.
+    ///
+    ///   0. `[writable]` Token-swap
+    ///   1. `[]` swap authority
+    ///   2. `[]` user transfer authority
+    ///   3. `[writable]` Pool mint account, swap authority is the owner
+    ///   4. `[writable]` SOURCE Pool account, amount is transferable by user
+    ///      transfer authority.
+    ///   5. `[writable]` token_a Swap Account to withdraw FROM.
+    ///   6. `[writable]` token_b Swap Account to withdraw FROM.
+    ///   7. `[writable]` token_a user Account to credit.
+    ///   8. `[writable]` token_b user Account to credit.
+    ///   9. `[]` Pool fee account
+    ///   10. `[signer]` Pool owner
+    ///   11. `[]` Token A mint
+    ///   12. `[]` Token B mint
+    ///   13. `[]` Pool Token program id
+    ///   14. `[]` Token A program id
+    ///   15. `[]` Token B program id
+    ///   16. `[]` Program config, see `SetConfig`
+    EmergencyWithdrawProportional(EmergencyWithdrawProportional),
+
+    ///   Deposit one type of token into the pool by swapping about half of it
+    ///   for the other type, paying the trade fee, and depositing both at the
+    ///   resulting ratio. The part swapped is chosen so that both sides are
+    ///   deposited in full; only rounding dust is left in the SOURCE account.
+    ///   Fails if the swap moves the price by more than the optional cap.
+    ///
+    ///   Takes the same accounts as `DepositSingleTokenTypeExactAmountIn`.
+    DepositSingleAutoBalance(DepositSingleAutoBalance),
+
+    ///   Compare the pool's spot price of token A in token B with an external
+    ///   reference price, for keepers looking for arbitrage. Changes nothing
+    ///   and sets the return data to the pool's spot price (u128), the
+    ///   deviation from the reference in basis points (i64, positive when the
+    ///   pool is above the reference), the direction of the trade that
+    ///   realigns the pool (u8, 0 for A to B, 1 for B to A) and the
+    ///   approximate source amount of that trade before fees (u64, 0 if the
+    ///   pool is aligned or the curve cannot move its price), all little
+    ///   endian.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` token_a Swap Account
+    ///   2. `[]` token_b Swap Account
+    GetImpliedPriceDeviation(GetImpliedPriceDeviation),
+
+    ///   Initializes a new swap like `Initialize`, first initializing the
+    ///   pool token mint with the swap authority as its mint authority, and
+    ///   the pool fee and destination accounts for it. The mint and both
+    ///   token accounts must already be allocated and owned by the pool
+    ///   token program, e.g. by system program `CreateAccount` instructions
+    ///   earlier in the same transaction.
+    ///
+    ///   0. `[writable, signer]` New Token-swap to create.
+    ///   1. `[]` swap authority derived from
+    ///      `create_program_address(&[Token-swap account])`
+    ///   2. `[]` token_a Account. Must be non zero, owned by swap authority.
+    ///   3. `[]` token_b Account. Must be non zero, owned by swap authority.
+    ///   4. `[writable]` Pool Token Mint, uninitialized.
+    ///   5. `[writable]` Pool Token Account to deposit trading and withdraw
+    ///      fees, uninitialized.
+    ///   6. `[writable]` Pool Token Account to deposit the initial pool token
+    ///      supply, uninitialized.
+    ///   7. `[]` Pool Token program id
+    ///   8. `[]` Rent sysvar
+    ///   9. `[]` Owner of the pool fee account, not the swap authority
+    ///   10. `[]` Owner of the initial pool token account, not the swap
+    ///       authority
+    ///   11. `[]` Token A mint, optional, checked as for `Initialize`
+    ///   12. `[]` Token B mint, optional, checked as for `Initialize`
+    ///   13. `[writable, signer]` Payer of the creation fee, only if charged,
+    ///       as for `Initialize`
+    ///   14. `[writable]` Treasury receiving the creation fee, only if charged
+    ///   15. `[]` System program, only if a creation fee is charged
+    InitializeWithMint(InitializeWithMint),
+
+    ///   Check that the pool token supply equals the pool tokens the swap has
+    ///   minted less those it has burned, failing if they differ, e.g.
+    ///   because pool tokens were minted outside the swap. Changes nothing.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` Pool Token Mint
+    VerifySupply,
+
+    ///   Itemize the fees of a swap of `amount_in` at the pool's current
+    ///   reserves and fees, as `Swap` would charge them without a fee
+    ///   discount. Changes nothing and sets the return data to the trading
+    ///   fee left to liquidity providers in the SOURCE token, the pool
+    ///   tokens minted as owner fee to the pool fee account, the pool tokens
+    ///   minted as host fee if a host fee account is given, and the
+    ///   DESTINATION amount received net of any transfer fee, each a little
+    ///   endian u64.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` token_a Swap Account
+    ///   2. `[]` token_b Swap Account
+    ///   3. `[]` Pool Token Mint
+    ///   4. `[]` Token A mint
+    ///   5. `[]` Token B mint
+    QuoteFees(QuoteFees),
+
+    ///   Transfer the whole balance of a token account owned by the swap
+    ///   authority, other than the pool's reserves and pool fee account, e.g.
+    ///   an associated token account tokens were sent to by mistake. Must be
+    ///   approved by the pool owner, see `SetConfig`.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` swap authority
+    ///   2. `[writable]` token Account to recover FROM, owned by the swap
+    ///      authority.
+    ///   3. `[writable]` token Account to credit.
+    ///   4. `[]` Pool fee account
+    ///   5. `[signer]` Pool owner
+    ///   6. `[]` Mint of the recovered token
+    ///   7. `[]` Token program id of the recovered token
+    ///   8. `[]` Program config, see `SetConfig`
+    RecoverTokens,
+
+    ///   Burn pool tokens collected as owner fees from the pool fee account
+    ///   and transfer the reserves they are worth to the pool owner, see
+    ///   `SetConfig`, without a withdrawal fee.
+    ///
+    ///   0. `[writable]` Token-swap
+    ///   1. `[]` swap authority
+    ///   2. `[writable]` Pool mint account, swap authority is the owner
+    ///   3. `[writable]` Pool fee account to burn the pool tokens FROM
+    ///   4. `[writable]` token_a Swap Account to withdraw FROM.
+    ///   5. `[writable]` token_b Swap Account to withdraw FROM.
+    ///   6. `[writable]` token_a Account to credit.
+    ///   7. `[writable]` token_b Account to credit.
+    ///   8. `[signer]` Pool owner
+    ///   9. `[]` Token A mint
+    ///   10. `[]` Token B mint
+    ///   11. `[]` Pool Token program id
+    ///   12. `[]` Token A program id
+    ///   13. `[]` Token B program id
+    ///   14. `[]` Program config, see `SetConfig`
+    RedeemOwnerFees(RedeemOwnerFees),
+
+    ///   Swap the tokens in the pool, as for `Swap`, and split the output
+    ///   between two DESTINATION accounts by `first_destination_bps`.
+    ///
+    ///   0-13. As for `Swap`, with 6. the first DESTINATION account
+    ///   14. `[writable]` token_(A|B) second DESTINATION Account, receiving
+    ///       the rest of the output.
+    ///   15. `[optional, writable]` Host fee account(s), followed by the
+    ///       optional governance and fee sweep accounts, as for `Swap`.
+    SwapSplit(SwapSplit),
+
+    ///   Replace the pool fee account receiving owner and withdrawal fees.
+    ///   Must be approved by the pool owner, see `SetConfig`, who must also
+    ///   own the new one.
+    ///
+    ///   0. `[writable]` Token-swap
+    ///   1. `[writable]` Current pool fee account
+    ///   2. `[]` New pool fee account, for the pool mint
+    ///   3. `[signer]` Pool owner
+    ///   4. `[]` Pool mint
+    ///   5. `[]` Pool Token program id
+    ///   6. `[]` Program config, see `SetConfig`
+    ///   7. `[optional, writable]` Pool token account to move the current pool
+    ///      fee account's balance to, in the same call.
+    SetFeeAccount,
+
+    ///   Report the curve invariant of the pool's current reserves, e.g. `k`
+    ///   for the constant product curve, to monitor the pool's health across
+    ///   swaps. Changes nothing and sets the return data to the invariant
+    ///   (u128, little endian).
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` token_a Swap Account
+    ///   2. `[]` token_b Swap Account
+    GetInvariant,
+
+    ///   Deposit both types of tokens into the pool as for
+    ///   `DepositAllTokenTypes`, then invoke a target program, e.g. to stake
+    ///   the freshly minted pool tokens, in the same instruction. The target
+    ///   is invoked with `cpi_data`, and with the pool account that received
+    ///   the pool tokens as its first account, followed by the accounts after
+    ///   the target. The user's signatures carry over to the target, the swap
+    ///   authority's never do. The target cannot be the swap program itself.
+    ///
+    ///   0-13. As for `DepositAllTokenTypes`, with 8. the pool account
+    ///   14-16. Deposit record, payer and system program as for
+    ///       `DepositAllTokenTypes`, only if the swap has a withdraw cooldown
+    ///   14. `[]` Target program id, or 17. if the swap has a withdraw
+    ///       cooldown
+    ///   15. `[]` Any further accounts of the target's instruction, passed in
+    ///       order with their signer and writable flags.
+    DepositAndCpi(DepositAndCpi),
+
+    ///   Quote what `WithdrawAllTokenTypes` pays out for `pool_token_amount`
+    ///   pool tokens at the pool's current reserves, e.g. to preview a
+    ///   redemption. Changes nothing and sets the return data to the token A
+    ///   and token B amounts, after the owner withdraw fee, and the withdraw
+    ///   fee in pool tokens, each a little endian u64, as the first 24 bytes
+    ///   of the withdrawal's return data. The fee is the one any holder other
+    ///   than the pool fee account pays, and transfer fees are not deducted.
+    ///
+    ///   0. `[]` Token-swap
+    ///   1. `[]` token_a Swap Account
+    ///   2. `[]` token_b Swap Account
+    ///   3. `[]` Pool Token Mint
+    QuoteWithdrawAll(QuoteWithdrawAll),
+
+    ///   Create or update the program config, whose owner approves
+    ///   owner-gated instructions such as `RecoverTokens` on swaps that don't
+    ///   set their own owner. The pool owner of a swap is its own owner if
+    ///   set, else the config's owner once the config is created, else the
+    ///   owner in the program's constraints, else the owner of the pool fee
+    ///   account. Owner-gated instructions take the config's address even
+    ///   before it is created. Must be
+    ///   approved by the config's current owner, or on creation by the owner
+    ///   in the program's constraints, so builds without constraints have no
+    ///   config.
+    ///
+    ///   0. `[writable]` Program config, at the address derived from
+    ///      `["config"]`
+    ///   1. `[signer]` Current owner
+    ///   2. `[writable, signer]` Payer, funding the config on creation
+    ///   3. `[]` System program
+    SetConfig(SetConfig),
 }
 
 impl SwapInstruction {
@@ -225,22 +814,34 @@ impl SwapInstruction {
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
         let (&tag, rest) = input.split_first().ok_or(SwapError::InvalidInstruction)?;
         Ok(match tag {
-            0 => {
-                if rest.len() >= Fees::LEN {
-                    let (fees, rest) = rest.split_at(Fees::LEN);
-                    let fees = Fees::unpack_unchecked(fees)?;
-                    let swap_curve = SwapCurve::unpack_unchecked(rest)?;
-                    Self::Initialize(Initialize { fees, swap_curve })
-                } else {
-                    return Err(SwapError::InvalidInstruction.into());
-                }
-            }
+            0 => Self::Initialize(Self::unpack_initialize(rest)?),
             1 => {
                 let (amount_in, rest) = Self::unpack_u64(rest)?;
-                let (minimum_amount_out, _rest) = Self::unpack_u64(rest)?;
+                let (minimum_amount_out, rest) = Self::unpack_u64(rest)?;
+                let (recipient, rest) = Self::unpack_pubkey_option(rest)?;
+                let (slippage_bps, rest) = Self::unpack_u16_option(rest)?;
+                let (max_price_impact_bps, rest) = Self::unpack_u16_option(rest)?;
+                let (allow_partial, rest) = Self::unpack_bool(rest)?;
+                let (max_owner_fee_pool_tokens, rest) = Self::unpack_u64_option(rest)?;
+                let (clamp_owner_fee, rest) = Self::unpack_bool(rest)?;
+                let (slippage_grace, rest) = match rest {
+                    [] => (0, rest),
+                    _ => Self::unpack_u64(rest)?,
+                };
+                let (quoted_amount_out, rest) = Self::unpack_u64_option(rest)?;
+                let memo = rest.to_vec();
                 Self::Swap(Swap {
                     amount_in,
                     minimum_amount_out,
+                    recipient,
+                    slippage_bps,
+                    max_price_impact_bps,
+                    allow_partial,
+                    max_owner_fee_pool_tokens,
+                    clamp_owner_fee,
+                    slippage_grace,
+                    quoted_amount_out,
+                    memo,
                 })
             }
             2 => {
@@ -256,11 +857,13 @@ impl SwapInstruction {
             3 => {
                 let (pool_token_amount, rest) = Self::unpack_u64(rest)?;
                 let (minimum_token_a_amount, rest) = Self::unpack_u64(rest)?;
-                let (minimum_token_b_amount, _rest) = Self::unpack_u64(rest)?;
+                let (minimum_token_b_amount, rest) = Self::unpack_u64(rest)?;
+                let (unwrap_sol, _rest) = Self::unpack_bool(rest)?;
                 Self::WithdrawAllTokenTypes(WithdrawAllTokenTypes {
                     pool_token_amount,
                     minimum_token_a_amount,
                     minimum_token_b_amount,
+                    unwrap_sol,
                 })
             }
             4 => {
@@ -273,16 +876,188 @@ impl SwapInstruction {
             }
             5 => {
                 let (destination_token_amount, rest) = Self::unpack_u64(rest)?;
-                let (maximum_pool_token_amount, _rest) = Self::unpack_u64(rest)?;
+                let (maximum_pool_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (unwrap_sol, _rest) = Self::unpack_bool(rest)?;
                 Self::WithdrawSingleTokenTypeExactAmountOut(WithdrawSingleTokenTypeExactAmountOut {
                     destination_token_amount,
                     maximum_pool_token_amount,
+                    unwrap_sol,
+                })
+            }
+            6 => Self::Skim,
+            7 => {
+                let (maximum_token_a_amount, rest) = Self::unpack_u64(rest)?;
+                let (maximum_token_b_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_pool_token_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::DepositAllProportional(DepositAllProportional {
+                    maximum_token_a_amount,
+                    maximum_token_b_amount,
+                    minimum_pool_token_amount,
+                })
+            }
+            8 => {
+                let (pool_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_token_a_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_token_b_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::EmergencyWithdrawProportional(EmergencyWithdrawProportional {
+                    pool_token_amount,
+                    minimum_token_a_amount,
+                    minimum_token_b_amount,
+                })
+            }
+            9 => {
+                let (source_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_pool_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (max_internal_impact_bps, _rest) = Self::unpack_u16_option(rest)?;
+                Self::DepositSingleAutoBalance(DepositSingleAutoBalance {
+                    source_token_amount,
+                    minimum_pool_token_amount,
+                    max_internal_impact_bps,
+                })
+            }
+            10 => {
+                let (reference_price, _rest) = Self::unpack_u128(rest)?;
+                Self::GetImpliedPriceDeviation(GetImpliedPriceDeviation { reference_price })
+            }
+            11 => {
+                let (&decimals, rest) = rest.split_first().ok_or(SwapError::InvalidInstruction)?;
+                Self::InitializeWithMint(InitializeWithMint {
+                    decimals,
+                    initialize: Self::unpack_initialize(rest)?,
+                })
+            }
+            12 => Self::VerifySupply,
+            13 => {
+                let (amount_in, rest) = Self::unpack_u64(rest)?;
+                let trade_direction = match rest.first() {
+                    Some(0) => TradeDirection::AtoB,
+                    Some(1) => TradeDirection::BtoA,
+                    _ => return Err(SwapError::InvalidInstruction.into()),
+                };
+                Self::QuoteFees(QuoteFees {
+                    amount_in,
+                    trade_direction,
+                })
+            }
+            14 => Self::RecoverTokens,
+            15 => {
+                let (pool_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_token_a_amount, rest) = Self::unpack_u64(rest)?;
+                let (minimum_token_b_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::RedeemOwnerFees(RedeemOwnerFees {
+                    pool_token_amount,
+                    minimum_token_a_amount,
+                    minimum_token_b_amount,
+                })
+            }
+            16 => {
+                let (amount_in, rest) = Self::unpack_u64(rest)?;
+                let (minimum_amount_out, rest) = Self::unpack_u64(rest)?;
+                let (first_destination_bps, _rest) = Self::unpack_u16(rest)?;
+                Self::SwapSplit(SwapSplit {
+                    amount_in,
+                    minimum_amount_out,
+                    first_destination_bps,
+                })
+            }
+            17 => Self::SetFeeAccount,
+            18 => Self::GetInvariant,
+            19 => {
+                let (pool_token_amount, rest) = Self::unpack_u64(rest)?;
+                let (maximum_token_a_amount, rest) = Self::unpack_u64(rest)?;
+                let (maximum_token_b_amount, rest) = Self::unpack_u64(rest)?;
+                Self::DepositAndCpi(DepositAndCpi {
+                    pool_token_amount,
+                    maximum_token_a_amount,
+                    maximum_token_b_amount,
+                    cpi_data: rest.to_vec(),
                 })
             }
+            20 => {
+                let (pool_token_amount, _rest) = Self::unpack_u64(rest)?;
+                Self::QuoteWithdrawAll(QuoteWithdrawAll { pool_token_amount })
+            }
+            21 => {
+                let (owner, _rest) = Self::unpack_pubkey(rest)?;
+                Self::SetConfig(SetConfig { owner })
+            }
             _ => return Err(SwapError::InvalidInstruction.into()),
         })
     }
 
+    /// Unpacks the data of an `Initialize` instruction, after the tag.
+    fn unpack_initialize(rest: &[u8]) -> Result<Initialize, ProgramError> {
+        if rest.len() >= Fees::LEN {
+            let (fees, rest) = rest.split_at(Fees::LEN);
+            let fees = Fees::unpack_unchecked(fees)?;
+            let (swap_curve, rest) = rest.split_at(rest.len().min(SwapCurve::LEN));
+            let swap_curve = SwapCurve::unpack_unchecked(swap_curve)?;
+            let (fee_mode, rest) = match rest.split_first() {
+                Some((&fee_mode, rest)) => (
+                    FeeMode::try_from(fee_mode).map_err(|_| SwapError::InvalidInstruction)?,
+                    rest,
+                ),
+                None => (FeeMode::default(), rest),
+            };
+            let (fee_discount, rest) = Self::unpack_trailing::<FeeDiscount>(rest)?;
+            let (host_fee_split, rest) = Self::unpack_trailing::<HostFeeSplit>(rest)?;
+            let (withdraw_cooldown_secs, rest) = match rest {
+                [] => (0, rest),
+                _ => Self::unpack_u64(rest)?,
+            };
+            let (host_fees_enabled, rest) = match rest {
+                [] => (true, rest),
+                [1, rest @ ..] => (true, rest),
+                [0, rest @ ..] => (false, rest),
+                _ => return Err(SwapError::InvalidInstruction.into()),
+            };
+            let (max_owner_fee_bps, rest) = match rest {
+                [] => (0, rest),
+                [low, high, rest @ ..] => (u16::from_le_bytes([*low, *high]), rest),
+                _ => return Err(SwapError::InvalidInstruction.into()),
+            };
+            let (min_trade_amount, rest) = Self::unpack_u64_option(rest)?;
+            let (allow_pool_mint_transfer_fee, rest) = Self::unpack_bool(rest)?;
+            let (fee_sweep, rest) = Self::unpack_trailing::<FeeSweep>(rest)?;
+            let (min_reserve_for_swap, rest) = Self::unpack_u64_option(rest)?;
+            let (host_fee_base, rest) = match rest.split_first() {
+                Some((&host_fee_base, rest)) => (
+                    HostFeeBase::try_from(host_fee_base)
+                        .map_err(|_| SwapError::InvalidInstruction)?,
+                    rest,
+                ),
+                None => (HostFeeBase::default(), rest),
+            };
+            let (directional_fees, rest) = Self::unpack_trailing::<DirectionalFees>(rest)?;
+            let (launch_fee, rest) = Self::unpack_trailing::<LaunchFee>(rest)?;
+            let (allowed_caller, rest) = Self::unpack_pubkey_option(rest)?;
+            let (balanced_deposits_only, rest) = Self::unpack_bool(rest)?;
+            let (owner, _rest) = Self::unpack_pubkey_option(rest)?;
+            Ok(Initialize {
+                fees,
+                swap_curve,
+                fee_mode,
+                fee_discount,
+                host_fee_split,
+                withdraw_cooldown_secs,
+                host_fees_enabled,
+                max_owner_fee_bps,
+                min_trade_amount,
+                allow_pool_mint_transfer_fee,
+                fee_sweep,
+                min_reserve_for_swap,
+                host_fee_base,
+                directional_fees,
+                launch_fee,
+                allowed_caller,
+                balanced_deposits_only,
+                owner,
+            })
+        } else {
+            Err(SwapError::InvalidInstruction.into())
+        }
+    }
+
     fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
         if input.len() >= 8 {
             let (amount, rest) = input.split_at(8);
@@ -297,26 +1072,191 @@ impl SwapInstruction {
         }
     }
 
+    fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+        if input.len() >= 32 {
+            let (key, rest) = input.split_at(32);
+            let key = Pubkey::try_from(key).map_err(|_| SwapError::InvalidInstruction)?;
+            Ok((key, rest))
+        } else {
+            Err(SwapError::InvalidInstruction.into())
+        }
+    }
+
+    fn unpack_u16(input: &[u8]) -> Result<(u16, &[u8]), ProgramError> {
+        if input.len() >= 2 {
+            let (amount, rest) = input.split_at(2);
+            Ok((u16::from_le_bytes([amount[0], amount[1]]), rest))
+        } else {
+            Err(SwapError::InvalidInstruction.into())
+        }
+    }
+
+    fn unpack_u128(input: &[u8]) -> Result<(u128, &[u8]), ProgramError> {
+        if input.len() >= 16 {
+            let (amount, rest) = input.split_at(16);
+            let amount = amount
+                .try_into()
+                .map(u128::from_le_bytes)
+                .map_err(|_| SwapError::InvalidInstruction)?;
+            Ok((amount, rest))
+        } else {
+            Err(SwapError::InvalidInstruction.into())
+        }
+    }
+
+    /// Unpacks an optional trailing packed value, defaulting when the data
+    /// ends before it. A partial value is rejected.
+    fn unpack_trailing<T: Pack + Default>(input: &[u8]) -> Result<(T, &[u8]), ProgramError> {
+        if input.is_empty() {
+            Ok((T::default(), input))
+        } else if input.len() >= T::LEN {
+            let (value, rest) = input.split_at(T::LEN);
+            Ok((T::unpack_from_slice(value)?, rest))
+        } else {
+            Err(SwapError::InvalidInstruction.into())
+        }
+    }
+
+    /// Unpacks an optional trailing pubkey, encoded as a one byte tag followed
+    /// by the key when the tag is 1. Missing data is treated as `None` so that
+    /// older clients remain compatible.
+    fn unpack_pubkey_option(input: &[u8]) -> Result<(Option<Pubkey>, &[u8]), ProgramError> {
+        match input.split_first() {
+            None => Ok((None, input)),
+            Some((&0, rest)) => Ok((None, rest)),
+            Some((&1, rest)) if rest.len() >= 32 => {
+                let (key, rest) = rest.split_at(32);
+                let key = Pubkey::try_from(key).map_err(|_| SwapError::InvalidInstruction)?;
+                Ok((Some(key), rest))
+            }
+            _ => Err(SwapError::InvalidInstruction.into()),
+        }
+    }
+
+    /// Unpacks an optional trailing u16, encoded the same way as
+    /// `unpack_pubkey_option`.
+    fn unpack_u16_option(input: &[u8]) -> Result<(Option<u16>, &[u8]), ProgramError> {
+        match input.split_first() {
+            None => Ok((None, input)),
+            Some((&0, rest)) => Ok((None, rest)),
+            Some((&1, rest)) if rest.len() >= 2 => {
+                let (value, rest) = rest.split_at(2);
+                let value = u16::from_le_bytes([value[0], value[1]]);
+                Ok((Some(value), rest))
+            }
+            _ => Err(SwapError::InvalidInstruction.into()),
+        }
+    }
+
+    /// Unpacks an optional trailing u64, encoded the same way as
+    /// `unpack_pubkey_option`.
+    fn unpack_u64_option(input: &[u8]) -> Result<(Option<u64>, &[u8]), ProgramError> {
+        match input.split_first() {
+            None => Ok((None, input)),
+            Some((&0, rest)) => Ok((None, rest)),
+            Some((&1, rest)) => {
+                let (value, rest) = Self::unpack_u64(rest)?;
+                Ok((Some(value), rest))
+            }
+            _ => Err(SwapError::InvalidInstruction.into()),
+        }
+    }
+
+    /// Unpacks an optional trailing flag, a single byte that defaults to
+    /// `false` when missing.
+    fn unpack_bool(input: &[u8]) -> Result<(bool, &[u8]), ProgramError> {
+        match input.split_first() {
+            None => Ok((false, input)),
+            Some((&0, rest)) => Ok((false, rest)),
+            Some((&1, rest)) => Ok((true, rest)),
+            _ => Err(SwapError::InvalidInstruction.into()),
+        }
+    }
+
     /// Packs a [SwapInstruction](enum.SwapInstruction.html) into a byte buffer.
     pub fn pack(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(size_of::<Self>());
         match self {
-            Self::Initialize(Initialize { fees, swap_curve }) => {
+            Self::Initialize(initialize) => {
                 buf.push(0);
-                let mut fees_slice = [0u8; Fees::LEN];
-                Pack::pack_into_slice(fees, &mut fees_slice[..]);
-                buf.extend_from_slice(&fees_slice);
-                let mut swap_curve_slice = [0u8; SwapCurve::LEN];
-                Pack::pack_into_slice(swap_curve, &mut swap_curve_slice[..]);
-                buf.extend_from_slice(&swap_curve_slice);
+                Self::pack_initialize(initialize, &mut buf);
             }
             Self::Swap(Swap {
                 amount_in,
                 minimum_amount_out,
+                recipient,
+                slippage_bps,
+                max_price_impact_bps,
+                allow_partial,
+                max_owner_fee_pool_tokens,
+                clamp_owner_fee,
+                slippage_grace,
+                quoted_amount_out,
+                memo,
             }) => {
                 buf.push(1);
                 buf.extend_from_slice(&amount_in.to_le_bytes());
                 buf.extend_from_slice(&minimum_amount_out.to_le_bytes());
+                // trailing options are omitted when unset, but an earlier one
+                // must still be tagged if a later one follows
+                let quote_follows = !memo.is_empty();
+                let grace_follows = quote_follows || quoted_amount_out.is_some();
+                let clamp_follows = grace_follows || *slippage_grace != 0;
+                let owner_fee_follows = clamp_follows || *clamp_owner_fee;
+                let partial_follows = owner_fee_follows || max_owner_fee_pool_tokens.is_some();
+                let impact_follows = partial_follows || *allow_partial;
+                let slippage_follows = impact_follows || max_price_impact_bps.is_some();
+                let recipient_follows = slippage_follows || slippage_bps.is_some();
+                match recipient {
+                    Some(recipient) => {
+                        buf.push(1);
+                        buf.extend_from_slice(recipient.as_ref());
+                    }
+                    None if recipient_follows => buf.push(0),
+                    None => {}
+                }
+                match slippage_bps {
+                    Some(slippage_bps) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&slippage_bps.to_le_bytes());
+                    }
+                    None if slippage_follows => buf.push(0),
+                    None => {}
+                }
+                match max_price_impact_bps {
+                    Some(max_price_impact_bps) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&max_price_impact_bps.to_le_bytes());
+                    }
+                    None if impact_follows => buf.push(0),
+                    None => {}
+                }
+                if impact_follows {
+                    buf.push(u8::from(*allow_partial));
+                }
+                match max_owner_fee_pool_tokens {
+                    Some(max_owner_fee_pool_tokens) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&max_owner_fee_pool_tokens.to_le_bytes());
+                    }
+                    None if owner_fee_follows => buf.push(0),
+                    None => {}
+                }
+                if owner_fee_follows {
+                    buf.push(u8::from(*clamp_owner_fee));
+                }
+                if clamp_follows {
+                    buf.extend_from_slice(&slippage_grace.to_le_bytes());
+                }
+                match quoted_amount_out {
+                    Some(quoted_amount_out) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&quoted_amount_out.to_le_bytes());
+                    }
+                    None if quote_follows => buf.push(0),
+                    None => {}
+                }
+                buf.extend_from_slice(memo);
             }
             Self::DepositAllTokenTypes(DepositAllTokenTypes {
                 pool_token_amount,
@@ -332,11 +1272,15 @@ impl SwapInstruction {
                 pool_token_amount,
                 minimum_token_a_amount,
                 minimum_token_b_amount,
+                unwrap_sol,
             }) => {
                 buf.push(3);
                 buf.extend_from_slice(&pool_token_amount.to_le_bytes());
                 buf.extend_from_slice(&minimum_token_a_amount.to_le_bytes());
                 buf.extend_from_slice(&minimum_token_b_amount.to_le_bytes());
+                if *unwrap_sol {
+                    buf.push(1);
+                }
             }
             Self::DepositSingleTokenTypeExactAmountIn(DepositSingleTokenTypeExactAmountIn {
                 source_token_amount,
@@ -350,18 +1294,203 @@ impl SwapInstruction {
                 WithdrawSingleTokenTypeExactAmountOut {
                     destination_token_amount,
                     maximum_pool_token_amount,
+                    unwrap_sol,
                 },
             ) => {
                 buf.push(5);
                 buf.extend_from_slice(&destination_token_amount.to_le_bytes());
                 buf.extend_from_slice(&maximum_pool_token_amount.to_le_bytes());
+                if *unwrap_sol {
+                    buf.push(1);
+                }
             }
-        }
-        buf
+            Self::Skim => buf.push(6),
+            Self::DepositAllProportional(DepositAllProportional {
+                maximum_token_a_amount,
+                maximum_token_b_amount,
+                minimum_pool_token_amount,
+            }) => {
+                buf.push(7);
+                buf.extend_from_slice(&maximum_token_a_amount.to_le_bytes());
+                buf.extend_from_slice(&maximum_token_b_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_pool_token_amount.to_le_bytes());
+            }
+            Self::EmergencyWithdrawProportional(EmergencyWithdrawProportional {
+                pool_token_amount,
+                minimum_token_a_amount,
+                minimum_token_b_amount,
+            }) => {
+                buf.push(8);
+                buf.extend_from_slice(&pool_token_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_token_a_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_token_b_amount.to_le_bytes());
+            }
+            Self::DepositSingleAutoBalance(DepositSingleAutoBalance {
+                source_token_amount,
+                minimum_pool_token_amount,
+                max_internal_impact_bps,
+            }) => {
+                buf.push(9);
+                buf.extend_from_slice(&source_token_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_pool_token_amount.to_le_bytes());
+                match max_internal_impact_bps {
+                    Some(max_internal_impact_bps) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&max_internal_impact_bps.to_le_bytes());
+                    }
+                    None => buf.push(0),
+                }
+            }
+            Self::GetImpliedPriceDeviation(GetImpliedPriceDeviation { reference_price }) => {
+                buf.push(10);
+                buf.extend_from_slice(&reference_price.to_le_bytes());
+            }
+            Self::InitializeWithMint(InitializeWithMint {
+                decimals,
+                initialize,
+            }) => {
+                buf.push(11);
+                buf.push(*decimals);
+                Self::pack_initialize(initialize, &mut buf);
+            }
+            Self::VerifySupply => buf.push(12),
+            Self::QuoteFees(QuoteFees {
+                amount_in,
+                trade_direction,
+            }) => {
+                buf.push(13);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.push(*trade_direction as u8);
+            }
+            Self::RecoverTokens => buf.push(14),
+            Self::RedeemOwnerFees(RedeemOwnerFees {
+                pool_token_amount,
+                minimum_token_a_amount,
+                minimum_token_b_amount,
+            }) => {
+                buf.push(15);
+                buf.extend_from_slice(&pool_token_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_token_a_amount.to_le_bytes());
+                buf.extend_from_slice(&minimum_token_b_amount.to_le_bytes());
+            }
+            Self::SwapSplit(SwapSplit {
+                amount_in,
+                minimum_amount_out,
+                first_destination_bps,
+            }) => {
+                buf.push(16);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&minimum_amount_out.to_le_bytes());
+                buf.extend_from_slice(&first_destination_bps.to_le_bytes());
+            }
+            Self::SetFeeAccount => buf.push(17),
+            Self::GetInvariant => buf.push(18),
+            Self::DepositAndCpi(DepositAndCpi {
+                pool_token_amount,
+                maximum_token_a_amount,
+                maximum_token_b_amount,
+                cpi_data,
+            }) => {
+                buf.push(19);
+                buf.extend_from_slice(&pool_token_amount.to_le_bytes());
+                buf.extend_from_slice(&maximum_token_a_amount.to_le_bytes());
+                buf.extend_from_slice(&maximum_token_b_amount.to_le_bytes());
+                buf.extend_from_slice(cpi_data);
+            }
+            Self::QuoteWithdrawAll(QuoteWithdrawAll { pool_token_amount }) => {
+                buf.push(20);
+                buf.extend_from_slice(&pool_token_amount.to_le_bytes());
+            }
+            Self::SetConfig(SetConfig { owner }) => {
+                buf.push(21);
+                buf.extend_from_slice(owner.as_ref());
+            }
+        }
+        buf
+    }
+
+    /// Packs the data of an `Initialize` instruction, after the tag.
+    fn pack_initialize(initialize: &Initialize, buf: &mut Vec<u8>) {
+        let Initialize {
+            fees,
+            swap_curve,
+            fee_mode,
+            fee_discount,
+            host_fee_split,
+            withdraw_cooldown_secs,
+            host_fees_enabled,
+            max_owner_fee_bps,
+            min_trade_amount,
+            allow_pool_mint_transfer_fee,
+            fee_sweep,
+            min_reserve_for_swap,
+            host_fee_base,
+            directional_fees,
+            launch_fee,
+            allowed_caller,
+            balanced_deposits_only,
+            owner,
+        } = initialize;
+        let mut fees_slice = [0u8; Fees::LEN];
+        Pack::pack_into_slice(fees, &mut fees_slice[..]);
+        buf.extend_from_slice(&fees_slice);
+        let mut swap_curve_slice = [0u8; SwapCurve::LEN];
+        Pack::pack_into_slice(swap_curve, &mut swap_curve_slice[..]);
+        buf.extend_from_slice(&swap_curve_slice);
+        buf.push(*fee_mode as u8);
+        let mut fee_discount_slice = [0u8; FeeDiscount::LEN];
+        fee_discount.pack_into_slice(&mut fee_discount_slice);
+        buf.extend_from_slice(&fee_discount_slice);
+        let mut host_fee_split_slice = [0u8; HostFeeSplit::LEN];
+        host_fee_split.pack_into_slice(&mut host_fee_split_slice);
+        buf.extend_from_slice(&host_fee_split_slice);
+        buf.extend_from_slice(&withdraw_cooldown_secs.to_le_bytes());
+        buf.push(*host_fees_enabled as u8);
+        buf.extend_from_slice(&max_owner_fee_bps.to_le_bytes());
+        match min_trade_amount {
+            Some(min_trade_amount) => {
+                buf.push(1);
+                buf.extend_from_slice(&min_trade_amount.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.push(*allow_pool_mint_transfer_fee as u8);
+        let mut fee_sweep_slice = [0u8; FeeSweep::LEN];
+        fee_sweep.pack_into_slice(&mut fee_sweep_slice);
+        buf.extend_from_slice(&fee_sweep_slice);
+        match min_reserve_for_swap {
+            Some(min_reserve_for_swap) => {
+                buf.push(1);
+                buf.extend_from_slice(&min_reserve_for_swap.to_le_bytes());
+            }
+            None => buf.push(0),
+        }
+        buf.push(*host_fee_base as u8);
+        let mut directional_fees_slice = [0u8; DirectionalFees::LEN];
+        directional_fees.pack_into_slice(&mut directional_fees_slice);
+        buf.extend_from_slice(&directional_fees_slice);
+        let mut launch_fee_slice = [0u8; LaunchFee::LEN];
+        launch_fee.pack_into_slice(&mut launch_fee_slice);
+        buf.extend_from_slice(&launch_fee_slice);
+        match allowed_caller {
+            Some(allowed_caller) => {
+                buf.push(1);
+                buf.extend_from_slice(allowed_caller.as_ref());
+            }
+            None => buf.push(0),
+        }
+        buf.push(*balanced_deposits_only as u8);
+        match owner {
+            Some(owner) => {
+                buf.push(1);
+                buf.extend_from_slice(owner.as_ref());
+            }
+            None => buf.push(0),
+        }
     }
 }
 
-/// Creates an 'initialize' instruction.
+/// Creates an 'initialize' instruction, using the default pool settings.
 pub fn initialize(
     program_id: &Pubkey,
     token_program_id: &Pubkey,
@@ -375,8 +1504,38 @@ pub fn initialize(
     fees: Fees,
     swap_curve: SwapCurve,
 ) -> Result<Instruction, ProgramError> {
-    let init_data = SwapInstruction::Initialize(Initialize { fees, swap_curve });
-    let data = init_data.pack();
+    initialize_with_config(
+        program_id,
+        token_program_id,
+        swap_pubkey,
+        authority_pubkey,
+        token_a_pubkey,
+        token_b_pubkey,
+        pool_pubkey,
+        fee_pubkey,
+        destination_pubkey,
+        Initialize {
+            fees,
+            swap_curve,
+            ..Initialize::default()
+        },
+    )
+}
+
+/// Creates an 'initialize' instruction with all pool settings provided.
+pub fn initialize_with_config(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    token_a_pubkey: &Pubkey,
+    token_b_pubkey: &Pubkey,
+    pool_pubkey: &Pubkey,
+    fee_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    instruction: Initialize,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::Initialize(instruction).pack();
 
     let accounts = vec![
         AccountMeta::new(*swap_pubkey, true),
@@ -396,6 +1555,44 @@ pub fn initialize(
     })
 }
 
+/// Creates an 'initialize_with_mint' instruction.
+pub fn initialize_with_mint(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    token_a_pubkey: &Pubkey,
+    token_b_pubkey: &Pubkey,
+    pool_pubkey: &Pubkey,
+    fee_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    fee_owner_pubkey: &Pubkey,
+    destination_owner_pubkey: &Pubkey,
+    instruction: InitializeWithMint,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::InitializeWithMint(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, true),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*token_a_pubkey, false),
+        AccountMeta::new_readonly(*token_b_pubkey, false),
+        AccountMeta::new(*pool_pubkey, false),
+        AccountMeta::new(*fee_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(*fee_owner_pubkey, false),
+        AccountMeta::new_readonly(*destination_owner_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
 /// Creates a 'deposit_all_token_types' instruction.
 pub fn deposit_all_token_types(
     program_id: &Pubkey,
@@ -418,7 +1615,7 @@ pub fn deposit_all_token_types(
     let data = SwapInstruction::DepositAllTokenTypes(instruction).pack();
 
     let accounts = vec![
-        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new(*swap_pubkey, false),
         AccountMeta::new_readonly(*authority_pubkey, false),
         AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
         AccountMeta::new(*deposit_token_a_pubkey, false),
@@ -441,45 +1638,55 @@ pub fn deposit_all_token_types(
     })
 }
 
-/// Creates a 'withdraw_all_token_types' instruction.
-pub fn withdraw_all_token_types(
+/// Creates a 'deposit_and_cpi' instruction.
+pub fn deposit_and_cpi(
     program_id: &Pubkey,
-    pool_token_program_id: &Pubkey,
     token_a_program_id: &Pubkey,
     token_b_program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
     swap_pubkey: &Pubkey,
     authority_pubkey: &Pubkey,
     user_transfer_authority_pubkey: &Pubkey,
-    pool_mint_pubkey: &Pubkey,
-    fee_account_pubkey: &Pubkey,
-    source_pubkey: &Pubkey,
+    deposit_token_a_pubkey: &Pubkey,
+    deposit_token_b_pubkey: &Pubkey,
     swap_token_a_pubkey: &Pubkey,
     swap_token_b_pubkey: &Pubkey,
-    destination_token_a_pubkey: &Pubkey,
-    destination_token_b_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
     token_a_mint_pubkey: &Pubkey,
     token_b_mint_pubkey: &Pubkey,
-    instruction: WithdrawAllTokenTypes,
+    deposit_record_and_payer_pubkeys: Option<(&Pubkey, &Pubkey)>,
+    target_program_id: &Pubkey,
+    target_accounts: &[AccountMeta],
+    instruction: DepositAndCpi,
 ) -> Result<Instruction, ProgramError> {
-    let data = SwapInstruction::WithdrawAllTokenTypes(instruction).pack();
+    let data = SwapInstruction::DepositAndCpi(instruction).pack();
 
-    let accounts = vec![
-        AccountMeta::new_readonly(*swap_pubkey, false),
+    let mut accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
         AccountMeta::new_readonly(*authority_pubkey, false),
         AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
-        AccountMeta::new(*pool_mint_pubkey, false),
-        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new(*deposit_token_a_pubkey, false),
+        AccountMeta::new(*deposit_token_b_pubkey, false),
         AccountMeta::new(*swap_token_a_pubkey, false),
         AccountMeta::new(*swap_token_b_pubkey, false),
-        AccountMeta::new(*destination_token_a_pubkey, false),
-        AccountMeta::new(*destination_token_b_pubkey, false),
-        AccountMeta::new(*fee_account_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
         AccountMeta::new_readonly(*token_a_mint_pubkey, false),
         AccountMeta::new_readonly(*token_b_mint_pubkey, false),
-        AccountMeta::new_readonly(*pool_token_program_id, false),
         AccountMeta::new_readonly(*token_a_program_id, false),
         AccountMeta::new_readonly(*token_b_program_id, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
     ];
+    if let Some((deposit_record_pubkey, payer_pubkey)) = deposit_record_and_payer_pubkeys {
+        accounts.extend([
+            AccountMeta::new(*deposit_record_pubkey, false),
+            AccountMeta::new(*payer_pubkey, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+    accounts.push(AccountMeta::new_readonly(*target_program_id, false));
+    accounts.extend_from_slice(target_accounts);
 
     Ok(Instruction {
         program_id: *program_id,
@@ -488,35 +1695,41 @@ pub fn withdraw_all_token_types(
     })
 }
 
-/// Creates a 'deposit_single_token_type_exact_amount_in' instruction.
-pub fn deposit_single_token_type_exact_amount_in(
+/// Creates a 'deposit_all_proportional' instruction.
+pub fn deposit_all_proportional(
     program_id: &Pubkey,
-    source_token_program_id: &Pubkey,
+    token_a_program_id: &Pubkey,
+    token_b_program_id: &Pubkey,
     pool_token_program_id: &Pubkey,
     swap_pubkey: &Pubkey,
     authority_pubkey: &Pubkey,
     user_transfer_authority_pubkey: &Pubkey,
-    source_token_pubkey: &Pubkey,
+    deposit_token_a_pubkey: &Pubkey,
+    deposit_token_b_pubkey: &Pubkey,
     swap_token_a_pubkey: &Pubkey,
     swap_token_b_pubkey: &Pubkey,
     pool_mint_pubkey: &Pubkey,
     destination_pubkey: &Pubkey,
-    source_mint_pubkey: &Pubkey,
-    instruction: DepositSingleTokenTypeExactAmountIn,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+    instruction: DepositAllProportional,
 ) -> Result<Instruction, ProgramError> {
-    let data = SwapInstruction::DepositSingleTokenTypeExactAmountIn(instruction).pack();
+    let data = SwapInstruction::DepositAllProportional(instruction).pack();
 
     let accounts = vec![
-        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new(*swap_pubkey, false),
         AccountMeta::new_readonly(*authority_pubkey, false),
         AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
-        AccountMeta::new(*source_token_pubkey, false),
+        AccountMeta::new(*deposit_token_a_pubkey, false),
+        AccountMeta::new(*deposit_token_b_pubkey, false),
         AccountMeta::new(*swap_token_a_pubkey, false),
         AccountMeta::new(*swap_token_b_pubkey, false),
         AccountMeta::new(*pool_mint_pubkey, false),
         AccountMeta::new(*destination_pubkey, false),
-        AccountMeta::new_readonly(*source_mint_pubkey, false),
-        AccountMeta::new_readonly(*source_token_program_id, false),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_a_program_id, false),
+        AccountMeta::new_readonly(*token_b_program_id, false),
         AccountMeta::new_readonly(*pool_token_program_id, false),
     ];
 
@@ -527,38 +1740,44 @@ pub fn deposit_single_token_type_exact_amount_in(
     })
 }
 
-/// Creates a 'withdraw_single_token_type_exact_amount_out' instruction.
-pub fn withdraw_single_token_type_exact_amount_out(
+/// Creates a 'withdraw_all_token_types' instruction.
+pub fn withdraw_all_token_types(
     program_id: &Pubkey,
     pool_token_program_id: &Pubkey,
-    destination_token_program_id: &Pubkey,
+    token_a_program_id: &Pubkey,
+    token_b_program_id: &Pubkey,
     swap_pubkey: &Pubkey,
     authority_pubkey: &Pubkey,
     user_transfer_authority_pubkey: &Pubkey,
     pool_mint_pubkey: &Pubkey,
     fee_account_pubkey: &Pubkey,
-    pool_token_source_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
     swap_token_a_pubkey: &Pubkey,
     swap_token_b_pubkey: &Pubkey,
-    destination_pubkey: &Pubkey,
-    destination_mint_pubkey: &Pubkey,
-    instruction: WithdrawSingleTokenTypeExactAmountOut,
+    destination_token_a_pubkey: &Pubkey,
+    destination_token_b_pubkey: &Pubkey,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+    instruction: WithdrawAllTokenTypes,
 ) -> Result<Instruction, ProgramError> {
-    let data = SwapInstruction::WithdrawSingleTokenTypeExactAmountOut(instruction).pack();
+    let data = SwapInstruction::WithdrawAllTokenTypes(instruction).pack();
 
     let accounts = vec![
-        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new(*swap_pubkey, false),
         AccountMeta::new_readonly(*authority_pubkey, false),
         AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
         AccountMeta::new(*pool_mint_pubkey, false),
-        AccountMeta::new(*pool_token_source_pubkey, false),
+        AccountMeta::new(*source_pubkey, false),
         AccountMeta::new(*swap_token_a_pubkey, false),
         AccountMeta::new(*swap_token_b_pubkey, false),
-        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new(*destination_token_a_pubkey, false),
+        AccountMeta::new(*destination_token_b_pubkey, false),
         AccountMeta::new(*fee_account_pubkey, false),
-        AccountMeta::new_readonly(*destination_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
         AccountMeta::new_readonly(*pool_token_program_id, false),
-        AccountMeta::new_readonly(*destination_token_program_id, false),
+        AccountMeta::new_readonly(*token_a_program_id, false),
+        AccountMeta::new_readonly(*token_b_program_id, false),
     ];
 
     Ok(Instruction {
@@ -568,47 +1787,49 @@ pub fn withdraw_single_token_type_exact_amount_out(
     })
 }
 
-/// Creates a 'swap' instruction.
-pub fn swap(
+/// Creates an 'emergency_withdraw_proportional' instruction.
+pub fn emergency_withdraw_proportional(
     program_id: &Pubkey,
-    source_token_program_id: &Pubkey,
-    destination_token_program_id: &Pubkey,
     pool_token_program_id: &Pubkey,
+    token_a_program_id: &Pubkey,
+    token_b_program_id: &Pubkey,
     swap_pubkey: &Pubkey,
     authority_pubkey: &Pubkey,
     user_transfer_authority_pubkey: &Pubkey,
-    source_pubkey: &Pubkey,
-    swap_source_pubkey: &Pubkey,
-    swap_destination_pubkey: &Pubkey,
-    destination_pubkey: &Pubkey,
     pool_mint_pubkey: &Pubkey,
-    pool_fee_pubkey: &Pubkey,
-    source_mint_pubkey: &Pubkey,
-    destination_mint_pubkey: &Pubkey,
-    host_fee_pubkey: Option<&Pubkey>,
-    instruction: Swap,
+    fee_account_pubkey: &Pubkey,
+    pool_owner_pubkey: &Pubkey,
+    config_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    destination_token_a_pubkey: &Pubkey,
+    destination_token_b_pubkey: &Pubkey,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+    instruction: EmergencyWithdrawProportional,
 ) -> Result<Instruction, ProgramError> {
-    let data = SwapInstruction::Swap(instruction).pack();
+    let data = SwapInstruction::EmergencyWithdrawProportional(instruction).pack();
 
-    let mut accounts = vec![
-        AccountMeta::new_readonly(*swap_pubkey, false),
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
         AccountMeta::new_readonly(*authority_pubkey, false),
         AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
-        AccountMeta::new(*source_pubkey, false),
-        AccountMeta::new(*swap_source_pubkey, false),
-        AccountMeta::new(*swap_destination_pubkey, false),
-        AccountMeta::new(*destination_pubkey, false),
         AccountMeta::new(*pool_mint_pubkey, false),
-        AccountMeta::new(*pool_fee_pubkey, false),
-        AccountMeta::new_readonly(*source_mint_pubkey, false),
-        AccountMeta::new_readonly(*destination_mint_pubkey, false),
-        AccountMeta::new_readonly(*source_token_program_id, false),
-        AccountMeta::new_readonly(*destination_token_program_id, false),
+        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*destination_token_a_pubkey, false),
+        AccountMeta::new(*destination_token_b_pubkey, false),
+        AccountMeta::new_readonly(*fee_account_pubkey, false),
+        AccountMeta::new_readonly(*pool_owner_pubkey, true),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
         AccountMeta::new_readonly(*pool_token_program_id, false),
+        AccountMeta::new_readonly(*token_a_program_id, false),
+        AccountMeta::new_readonly(*token_b_program_id, false),
+        AccountMeta::new_readonly(*config_pubkey, false),
     ];
-    if let Some(host_fee_pubkey) = host_fee_pubkey {
-        accounts.push(AccountMeta::new(*host_fee_pubkey, false));
-    }
 
     Ok(Instruction {
         program_id: *program_id,
@@ -617,83 +1838,1352 @@ pub fn swap(
     })
 }
 
-/// Unpacks a reference from a bytes buffer.
-/// TODO actually pack / unpack instead of relying on normal memory layout.
-pub fn unpack<T>(input: &[u8]) -> Result<&T, ProgramError> {
-    if input.len() < size_of::<u8>() + size_of::<T>() {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    #[allow(clippy::cast_ptr_alignment)]
-    let val: &T = unsafe { &*(&input[1] as *const u8 as *const T) };
-    Ok(val)
-}
-
-#[cfg(test)]
+/// Creates a 'deposit_single_token_type_exact_amount_in' instruction.
+pub fn deposit_single_token_type_exact_amount_in(
+    program_id: &Pubkey,
+    source_token_program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    user_transfer_authority_pubkey: &Pubkey,
+    source_token_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    source_mint_pubkey: &Pubkey,
+    instruction: DepositSingleTokenTypeExactAmountIn,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::DepositSingleTokenTypeExactAmountIn(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
+        AccountMeta::new(*source_token_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new_readonly(*source_mint_pubkey, false),
+        AccountMeta::new_readonly(*source_token_program_id, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'deposit_single_auto_balance' instruction.
+pub fn deposit_single_auto_balance(
+    program_id: &Pubkey,
+    source_token_program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    user_transfer_authority_pubkey: &Pubkey,
+    source_token_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    source_mint_pubkey: &Pubkey,
+    instruction: DepositSingleAutoBalance,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::DepositSingleAutoBalance(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
+        AccountMeta::new(*source_token_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new_readonly(*source_mint_pubkey, false),
+        AccountMeta::new_readonly(*source_token_program_id, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'get_implied_price_deviation' instruction.
+pub fn get_implied_price_deviation(
+    program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    instruction: GetImpliedPriceDeviation,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::GetImpliedPriceDeviation(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_a_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_b_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'verify_supply' instruction.
+pub fn verify_supply(
+    program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::VerifySupply.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*pool_mint_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'quote_fees' instruction.
+pub fn quote_fees(
+    program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+    instruction: QuoteFees,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::QuoteFees(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_a_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_b_pubkey, false),
+        AccountMeta::new_readonly(*pool_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'recover_tokens' instruction.
+pub fn recover_tokens(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    pool_fee_pubkey: &Pubkey,
+    pool_owner_pubkey: &Pubkey,
+    config_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::RecoverTokens.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new_readonly(*pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*pool_owner_pubkey, true),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(*config_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'redeem_owner_fees' instruction.
+pub fn redeem_owner_fees(
+    program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    token_a_program_id: &Pubkey,
+    token_b_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    fee_account_pubkey: &Pubkey,
+    pool_owner_pubkey: &Pubkey,
+    config_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    destination_token_a_pubkey: &Pubkey,
+    destination_token_b_pubkey: &Pubkey,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+    instruction: RedeemOwnerFees,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::RedeemOwnerFees(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*fee_account_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*destination_token_a_pubkey, false),
+        AccountMeta::new(*destination_token_b_pubkey, false),
+        AccountMeta::new_readonly(*pool_owner_pubkey, true),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+        AccountMeta::new_readonly(*token_a_program_id, false),
+        AccountMeta::new_readonly(*token_b_program_id, false),
+        AccountMeta::new_readonly(*config_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'withdraw_single_token_type_exact_amount_out' instruction.
+pub fn withdraw_single_token_type_exact_amount_out(
+    program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    destination_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    user_transfer_authority_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    fee_account_pubkey: &Pubkey,
+    pool_token_source_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    destination_mint_pubkey: &Pubkey,
+    instruction: WithdrawSingleTokenTypeExactAmountOut,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::WithdrawSingleTokenTypeExactAmountOut(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*pool_token_source_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new(*fee_account_pubkey, false),
+        AccountMeta::new_readonly(*destination_mint_pubkey, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+        AccountMeta::new_readonly(*destination_token_program_id, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'skim' instruction.
+pub fn skim(
+    program_id: &Pubkey,
+    token_a_program_id: &Pubkey,
+    token_b_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    destination_a_pubkey: &Pubkey,
+    destination_b_pubkey: &Pubkey,
+    pool_fee_pubkey: &Pubkey,
+    config_pubkey: &Pubkey,
+    token_a_mint_pubkey: &Pubkey,
+    token_b_mint_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::Skim.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new(*swap_token_a_pubkey, false),
+        AccountMeta::new(*swap_token_b_pubkey, false),
+        AccountMeta::new(*destination_a_pubkey, false),
+        AccountMeta::new(*destination_b_pubkey, false),
+        AccountMeta::new_readonly(*pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*token_a_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_b_mint_pubkey, false),
+        AccountMeta::new_readonly(*token_a_program_id, false),
+        AccountMeta::new_readonly(*token_b_program_id, false),
+        AccountMeta::new_readonly(*config_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'swap' instruction.
+pub fn swap(
+    program_id: &Pubkey,
+    source_token_program_id: &Pubkey,
+    destination_token_program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    user_transfer_authority_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
+    swap_source_pubkey: &Pubkey,
+    swap_destination_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    pool_fee_pubkey: &Pubkey,
+    source_mint_pubkey: &Pubkey,
+    destination_mint_pubkey: &Pubkey,
+    host_fee_pubkey: Option<&Pubkey>,
+    instruction: Swap,
+) -> Result<Instruction, ProgramError> {
+    let memo_follows = !instruction.memo.is_empty();
+    let data = SwapInstruction::Swap(instruction).pack();
+
+    let mut accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
+        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new(*swap_source_pubkey, false),
+        AccountMeta::new(*swap_destination_pubkey, false),
+        AccountMeta::new(*destination_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*source_mint_pubkey, false),
+        AccountMeta::new_readonly(*destination_mint_pubkey, false),
+        AccountMeta::new_readonly(*source_token_program_id, false),
+        AccountMeta::new_readonly(*destination_token_program_id, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+    ];
+    if let Some(host_fee_pubkey) = host_fee_pubkey {
+        accounts.push(AccountMeta::new(*host_fee_pubkey, false));
+    }
+    if memo_follows {
+        accounts.push(AccountMeta::new_readonly(spl_memo::id(), false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'swap_split' instruction.
+pub fn swap_split(
+    program_id: &Pubkey,
+    source_token_program_id: &Pubkey,
+    destination_token_program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    user_transfer_authority_pubkey: &Pubkey,
+    source_pubkey: &Pubkey,
+    swap_source_pubkey: &Pubkey,
+    swap_destination_pubkey: &Pubkey,
+    first_destination_pubkey: &Pubkey,
+    second_destination_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    pool_fee_pubkey: &Pubkey,
+    source_mint_pubkey: &Pubkey,
+    destination_mint_pubkey: &Pubkey,
+    host_fee_pubkey: Option<&Pubkey>,
+    instruction: SwapSplit,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::SwapSplit(instruction).pack();
+
+    let mut accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, false),
+        AccountMeta::new_readonly(*user_transfer_authority_pubkey, true),
+        AccountMeta::new(*source_pubkey, false),
+        AccountMeta::new(*swap_source_pubkey, false),
+        AccountMeta::new(*swap_destination_pubkey, false),
+        AccountMeta::new(*first_destination_pubkey, false),
+        AccountMeta::new(*pool_mint_pubkey, false),
+        AccountMeta::new(*pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*source_mint_pubkey, false),
+        AccountMeta::new_readonly(*destination_mint_pubkey, false),
+        AccountMeta::new_readonly(*source_token_program_id, false),
+        AccountMeta::new_readonly(*destination_token_program_id, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+        AccountMeta::new(*second_destination_pubkey, false),
+    ];
+    if let Some(host_fee_pubkey) = host_fee_pubkey {
+        accounts.push(AccountMeta::new(*host_fee_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'set_fee_account' instruction.
+pub fn set_fee_account(
+    program_id: &Pubkey,
+    pool_token_program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    pool_fee_pubkey: &Pubkey,
+    new_pool_fee_pubkey: &Pubkey,
+    pool_owner_pubkey: &Pubkey,
+    config_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    sweep_destination_pubkey: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::SetFeeAccount.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new(*swap_pubkey, false),
+        AccountMeta::new(*pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*new_pool_fee_pubkey, false),
+        AccountMeta::new_readonly(*pool_owner_pubkey, true),
+        AccountMeta::new_readonly(*pool_mint_pubkey, false),
+        AccountMeta::new_readonly(*pool_token_program_id, false),
+        AccountMeta::new_readonly(*config_pubkey, false),
+    ];
+    if let Some(sweep_destination_pubkey) = sweep_destination_pubkey {
+        accounts.push(AccountMeta::new(*sweep_destination_pubkey, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'get_invariant' instruction.
+pub fn get_invariant(
+    program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::GetInvariant.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_a_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_b_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'quote_withdraw_all' instruction.
+pub fn quote_withdraw_all(
+    program_id: &Pubkey,
+    swap_pubkey: &Pubkey,
+    swap_token_a_pubkey: &Pubkey,
+    swap_token_b_pubkey: &Pubkey,
+    pool_mint_pubkey: &Pubkey,
+    instruction: QuoteWithdrawAll,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::QuoteWithdrawAll(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*swap_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_a_pubkey, false),
+        AccountMeta::new_readonly(*swap_token_b_pubkey, false),
+        AccountMeta::new_readonly(*pool_mint_pubkey, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a 'set_config' instruction.
+pub fn set_config(
+    program_id: &Pubkey,
+    config_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    payer_pubkey: &Pubkey,
+    instruction: SetConfig,
+) -> Result<Instruction, ProgramError> {
+    let data = SwapInstruction::SetConfig(instruction).pack();
+
+    let accounts = vec![
+        AccountMeta::new(*config_pubkey, false),
+        AccountMeta::new_readonly(*owner_pubkey, true),
+        AccountMeta::new(*payer_pubkey, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Unpacks a reference from a bytes buffer.
+/// TODO actually pack / unpack instead of relying on normal memory layout.
+pub fn unpack<T>(input: &[u8]) -> Result<&T, ProgramError> {
+    if input.len() < size_of::<u8>() + size_of::<T>() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    #[allow(clippy::cast_ptr_alignment)]
+    let val: &T = unsafe { &*(&input[1] as *const u8 as *const T) };
+    Ok(val)
+}
+
+#[cfg(test)]
 mod tests {
     use {
         super::*,
-        crate::curve::{base::CurveType, offset::OffsetCurve},
+        crate::{
+            curve::{base::CurveType, offset::OffsetCurve},
+            state::{FeeDiscountTier, TradeFees},
+        },
         std::sync::Arc,
     };
 
     #[test]
-    fn pack_intialize() {
-        let trade_fee_numerator: u64 = 1;
-        let trade_fee_denominator: u64 = 4;
-        let owner_trade_fee_numerator: u64 = 2;
-        let owner_trade_fee_denominator: u64 = 5;
-        let owner_withdraw_fee_numerator: u64 = 1;
-        let owner_withdraw_fee_denominator: u64 = 3;
-        let host_fee_numerator: u64 = 5;
-        let host_fee_denominator: u64 = 20;
-        let fees = Fees {
-            trade_fee_numerator,
-            trade_fee_denominator,
-            owner_trade_fee_numerator,
-            owner_trade_fee_denominator,
-            owner_withdraw_fee_numerator,
-            owner_withdraw_fee_denominator,
-            host_fee_numerator,
-            host_fee_denominator,
-        };
-        let token_b_offset: u64 = 1_000_000_000;
-        let curve_type = CurveType::Offset;
-        let calculator = Arc::new(OffsetCurve { token_b_offset });
-        let swap_curve = SwapCurve {
-            curve_type,
-            calculator,
-        };
-        let check = SwapInstruction::Initialize(Initialize { fees, swap_curve });
+    fn pack_intialize() {
+        let trade_fee_numerator: u64 = 1;
+        let trade_fee_denominator: u64 = 4;
+        let owner_trade_fee_numerator: u64 = 2;
+        let owner_trade_fee_denominator: u64 = 5;
+        let owner_withdraw_fee_numerator: u64 = 1;
+        let owner_withdraw_fee_denominator: u64 = 3;
+        let host_fee_numerator: u64 = 5;
+        let host_fee_denominator: u64 = 20;
+        let fees = Fees {
+            trade_fee_numerator,
+            trade_fee_denominator,
+            owner_trade_fee_numerator,
+            owner_trade_fee_denominator,
+            owner_withdraw_fee_numerator,
+            owner_withdraw_fee_denominator,
+            host_fee_numerator,
+            host_fee_denominator,
+        };
+        let token_b_offset: u64 = 1_000_000_000;
+        let curve_type = CurveType::Offset;
+        let calculator = Arc::new(OffsetCurve { token_b_offset });
+        let swap_curve = SwapCurve {
+            curve_type,
+            calculator,
+        };
+        let fee_mode = FeeMode::RetainInPool;
+        let governance_mint = Pubkey::new_unique();
+        let fee_discount = FeeDiscount {
+            governance_mint,
+            tiers: [
+                FeeDiscountTier {
+                    threshold: 1_000,
+                    discount_bps: 2_500,
+                },
+                FeeDiscountTier::default(),
+                FeeDiscountTier::default(),
+            ],
+        };
+        let host_fee_split = HostFeeSplit {
+            weights: [1, 3, 0, 0],
+        };
+        let withdraw_cooldown_secs: u64 = 3_600;
+        let fee_sweep_destination = Pubkey::new_unique();
+        let fee_sweep = FeeSweep {
+            threshold: 5_000,
+            destination: fee_sweep_destination,
+        };
+        let allowed_caller = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let check = SwapInstruction::Initialize(Initialize {
+            fees,
+            swap_curve,
+            fee_mode,
+            fee_discount,
+            host_fee_split,
+            withdraw_cooldown_secs,
+            host_fees_enabled: false,
+            max_owner_fee_bps: 50,
+            min_trade_amount: Some(1_000),
+            allow_pool_mint_transfer_fee: true,
+            fee_sweep,
+            min_reserve_for_swap: Some(500),
+            host_fee_base: HostFeeBase::TradeFee,
+            directional_fees: DirectionalFees {
+                a_to_b: None,
+                b_to_a: Some(TradeFees {
+                    trade_fee_numerator: 30,
+                    trade_fee_denominator: 10_000,
+                    owner_trade_fee_numerator: 5,
+                    owner_trade_fee_denominator: 10_000,
+                }),
+            },
+            launch_fee: LaunchFee {
+                launch_fee_bps: 500,
+                floor_fee_bps: 30,
+                launch_time: 0,
+                decay_secs: 3_600,
+            },
+            allowed_caller: Some(allowed_caller),
+            balanced_deposits_only: true,
+            owner: Some(owner),
+        });
+        let packed = check.pack();
+        let mut expect = vec![0u8];
+        expect.extend_from_slice(&trade_fee_numerator.to_le_bytes());
+        expect.extend_from_slice(&trade_fee_denominator.to_le_bytes());
+        expect.extend_from_slice(&owner_trade_fee_numerator.to_le_bytes());
+        expect.extend_from_slice(&owner_trade_fee_denominator.to_le_bytes());
+        expect.extend_from_slice(&owner_withdraw_fee_numerator.to_le_bytes());
+        expect.extend_from_slice(&owner_withdraw_fee_denominator.to_le_bytes());
+        expect.extend_from_slice(&host_fee_numerator.to_le_bytes());
+        expect.extend_from_slice(&host_fee_denominator.to_le_bytes());
+        expect.push(curve_type as u8);
+        expect.extend_from_slice(&token_b_offset.to_le_bytes());
+        expect.extend_from_slice(&[0u8; 24]);
+        expect.push(fee_mode as u8);
+        expect.extend_from_slice(governance_mint.as_ref());
+        expect.extend_from_slice(&1_000u64.to_le_bytes());
+        expect.extend_from_slice(&2_500u16.to_le_bytes());
+        expect.extend_from_slice(&[0u8; 20]);
+        expect.extend_from_slice(&[1, 0, 3, 0, 0, 0, 0, 0]);
+        expect.extend_from_slice(&withdraw_cooldown_secs.to_le_bytes());
+        expect.push(0);
+        expect.extend_from_slice(&50u16.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(&1_000u64.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(&5_000u64.to_le_bytes());
+        expect.extend_from_slice(fee_sweep_destination.as_ref());
+        expect.push(1);
+        expect.extend_from_slice(&500u64.to_le_bytes());
+        expect.push(HostFeeBase::TradeFee as u8);
+        expect.extend_from_slice(&[0u8; 33]);
+        expect.push(1);
+        expect.extend_from_slice(&30u64.to_le_bytes());
+        expect.extend_from_slice(&10_000u64.to_le_bytes());
+        expect.extend_from_slice(&5u64.to_le_bytes());
+        expect.extend_from_slice(&10_000u64.to_le_bytes());
+        expect.extend_from_slice(&500u16.to_le_bytes());
+        expect.extend_from_slice(&30u16.to_le_bytes());
+        expect.extend_from_slice(&0i64.to_le_bytes());
+        expect.extend_from_slice(&3_600u64.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(allowed_caller.as_ref());
+        expect.push(1);
+        expect.push(1);
+        expect.extend_from_slice(owner.as_ref());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // data from older clients omits the owner
+        expect.truncate(expect.len() - 33);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { owner, .. }) => assert_eq!(owner, None),
+            _ => panic!("expected initialize"),
+        }
+
+        // invalid balanced deposits flag
+        *expect.last_mut().unwrap() = 2;
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the balanced deposits flag
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                balanced_deposits_only,
+                ..
+            }) => assert!(!balanced_deposits_only),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated allowed caller
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the allowed caller
+        expect.truncate(expect.len() - 32);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { allowed_caller, .. }) => {
+                assert_eq!(allowed_caller, None)
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated launch fee
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the launch fee
+        expect.truncate(expect.len() - (LaunchFee::LEN - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { launch_fee, .. }) => {
+                assert_eq!(launch_fee, LaunchFee::default())
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated directional fees
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the directional fees
+        expect.truncate(expect.len() - (DirectionalFees::LEN - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                directional_fees, ..
+            }) => assert_eq!(directional_fees, DirectionalFees::default()),
+            _ => panic!("expected initialize"),
+        }
+
+        // unknown host fee base
+        *expect.last_mut().unwrap() = 2;
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the host fee base
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { host_fee_base, .. }) => {
+                assert_eq!(host_fee_base, HostFeeBase::OwnerFee)
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated minimum reserve
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the minimum reserve
+        expect.truncate(expect.len() - 8);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                min_reserve_for_swap,
+                ..
+            }) => assert_eq!(min_reserve_for_swap, None),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated fee sweep
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the fee sweep
+        expect.truncate(expect.len() - (FeeSweep::LEN - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { fee_sweep, .. }) => {
+                assert_eq!(fee_sweep, FeeSweep::default())
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // invalid pool mint transfer fee flag
+        *expect.last_mut().unwrap() = 2;
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the pool mint transfer fee flag
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                allow_pool_mint_transfer_fee,
+                ..
+            }) => assert!(!allow_pool_mint_transfer_fee),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated minimum trade amount
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the minimum trade amount
+        expect.truncate(expect.len() - 8);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                min_trade_amount, ..
+            }) => assert_eq!(min_trade_amount, None),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated owner fee cap
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the owner fee cap
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                max_owner_fee_bps, ..
+            }) => assert_eq!(max_owner_fee_bps, 0),
+            _ => panic!("expected initialize"),
+        }
+
+        // invalid host fees flag
+        *expect.last_mut().unwrap() = 2;
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the host fees flag
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                host_fees_enabled, ..
+            }) => assert!(host_fees_enabled),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated withdraw cooldown
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the withdraw cooldown
+        expect.truncate(expect.len() - (size_of::<u64>() - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                withdraw_cooldown_secs,
+                ..
+            }) => assert_eq!(withdraw_cooldown_secs, 0),
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated host fee split
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the host fee split
+        expect.truncate(expect.len() - (HostFeeSplit::LEN - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { host_fee_split, .. }) => {
+                assert_eq!(host_fee_split, HostFeeSplit::default())
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // truncated fee discount
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the fee discount
+        expect.truncate(expect.len() - (FeeDiscount::LEN - 1));
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize {
+                fee_mode: unpacked_fee_mode,
+                fee_discount,
+                ..
+            }) => {
+                assert_eq!(unpacked_fee_mode, fee_mode);
+                assert_eq!(fee_discount, FeeDiscount::default());
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // and the fee mode
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        match unpacked {
+            SwapInstruction::Initialize(Initialize { fee_mode, .. }) => {
+                assert_eq!(fee_mode, FeeMode::MintPoolTokens)
+            }
+            _ => panic!("expected initialize"),
+        }
+
+        // unknown fee mode
+        expect.push(2);
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_initialize_with_mint() {
+        let fees = Fees {
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 4,
+            ..Fees::default()
+        };
+        let swap_curve = SwapCurve {
+            curve_type: CurveType::Offset,
+            calculator: Arc::new(OffsetCurve {
+                token_b_offset: 1_000,
+            }),
+        };
+        let initialize = || Initialize {
+            fees: fees.clone(),
+            swap_curve: swap_curve.clone(),
+            min_trade_amount: Some(1_000),
+            ..Initialize::default()
+        };
+        let check = SwapInstruction::InitializeWithMint(InitializeWithMint {
+            decimals: 6,
+            initialize: initialize(),
+        });
+        let packed = check.pack();
+        // the tag and decimals, followed by the `Initialize` data
+        let mut expect = vec![11u8, 6];
+        expect.extend_from_slice(&SwapInstruction::Initialize(initialize()).pack()[1..]);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // missing decimals
+        assert_eq!(
+            SwapInstruction::unpack(&[11]),
+            Err(SwapError::InvalidInstruction.into())
+        );
+        // missing fees
+        assert_eq!(
+            SwapInstruction::unpack(&expect[..2 + Fees::LEN - 1]),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_swap() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 10;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: None,
+            slippage_bps: None,
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_swap_with_recipient() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 10;
+        let recipient = Pubkey::new_unique();
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: Some(recipient),
+            slippage_bps: None,
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(recipient.as_ref());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // explicit `None` tag
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(
+            unpacked,
+            SwapInstruction::Swap(Swap {
+                amount_in,
+                minimum_amount_out,
+                recipient: None,
+                slippage_bps: None,
+                max_price_impact_bps: None,
+                allow_partial: false,
+                max_owner_fee_pool_tokens: None,
+                clamp_owner_fee: false,
+                slippage_grace: 0,
+                quoted_amount_out: None,
+                memo: vec![],
+            })
+        );
+
+        // truncated key
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(&recipient.as_ref()[..16]);
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_swap_with_slippage_bps() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 8;
+        let slippage_bps: u16 = 50;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: None,
+            slippage_bps: Some(slippage_bps),
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        expect.push(1);
+        expect.extend_from_slice(&slippage_bps.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // truncated value
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // the quote the tolerance applies to trails the other options
+        let recipient = Pubkey::new_unique();
+        let quoted_amount_out: u64 = 10;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: Some(recipient),
+            slippage_bps: Some(slippage_bps),
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: Some(quoted_amount_out),
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(recipient.as_ref());
+        expect.push(1);
+        expect.extend_from_slice(&slippage_bps.to_le_bytes());
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.extend_from_slice(&0u64.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(&quoted_amount_out.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // truncated value
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_swap_with_partial_fill() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 10;
+        let max_price_impact_bps: u16 = 100;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: None,
+            slippage_bps: None,
+            max_price_impact_bps: Some(max_price_impact_bps),
+            allow_partial: true,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        expect.push(0);
+        expect.push(1);
+        expect.extend_from_slice(&max_price_impact_bps.to_le_bytes());
+        expect.push(1);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // the flag defaults to all-or-nothing when omitted
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(
+            unpacked,
+            SwapInstruction::Swap(Swap {
+                amount_in,
+                minimum_amount_out,
+                recipient: None,
+                slippage_bps: None,
+                max_price_impact_bps: Some(max_price_impact_bps),
+                allow_partial: false,
+                max_owner_fee_pool_tokens: None,
+                clamp_owner_fee: false,
+                slippage_grace: 0,
+                quoted_amount_out: None,
+                memo: vec![],
+            })
+        );
+
+        // invalid flag
+        expect.push(2);
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_swap_with_owner_fee_limit() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 10;
+        let max_owner_fee_pool_tokens: u64 = 1_000;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: None,
+            slippage_bps: None,
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: Some(max_owner_fee_pool_tokens),
+            clamp_owner_fee: true,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
+        let packed = check.pack();
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(1);
+        expect.extend_from_slice(&max_owner_fee_pool_tokens.to_le_bytes());
+        expect.push(1);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // exceeding the limit fails unless clamping is requested
+        expect.pop();
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(
+            unpacked,
+            SwapInstruction::Swap(Swap {
+                amount_in,
+                minimum_amount_out,
+                recipient: None,
+                slippage_bps: None,
+                max_price_impact_bps: None,
+                allow_partial: false,
+                max_owner_fee_pool_tokens: Some(max_owner_fee_pool_tokens),
+                clamp_owner_fee: false,
+                slippage_grace: 0,
+                quoted_amount_out: None,
+                memo: vec![],
+            })
+        );
+        assert_eq!(unpacked.pack(), expect);
+    }
+
+    #[test]
+    fn pack_swap_with_slippage_grace() {
+        let amount_in: u64 = 2;
+        let minimum_amount_out: u64 = 10;
+        let slippage_grace: u64 = 2;
+        let check = SwapInstruction::Swap(Swap {
+            amount_in,
+            minimum_amount_out,
+            recipient: None,
+            slippage_bps: None,
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace,
+            quoted_amount_out: None,
+            memo: vec![],
+        });
         let packed = check.pack();
-        let mut expect = vec![0u8];
-        expect.extend_from_slice(&trade_fee_numerator.to_le_bytes());
-        expect.extend_from_slice(&trade_fee_denominator.to_le_bytes());
-        expect.extend_from_slice(&owner_trade_fee_numerator.to_le_bytes());
-        expect.extend_from_slice(&owner_trade_fee_denominator.to_le_bytes());
-        expect.extend_from_slice(&owner_withdraw_fee_numerator.to_le_bytes());
-        expect.extend_from_slice(&owner_withdraw_fee_denominator.to_le_bytes());
-        expect.extend_from_slice(&host_fee_numerator.to_le_bytes());
-        expect.extend_from_slice(&host_fee_denominator.to_le_bytes());
-        expect.push(curve_type as u8);
-        expect.extend_from_slice(&token_b_offset.to_le_bytes());
-        expect.extend_from_slice(&[0u8; 24]);
+        let mut expect = vec![1];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.extend_from_slice(&slippage_grace.to_le_bytes());
         assert_eq!(packed, expect);
         let unpacked = SwapInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
+
+        // truncated value
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
     }
 
     #[test]
-    fn pack_swap() {
+    fn pack_swap_with_memo() {
         let amount_in: u64 = 2;
         let minimum_amount_out: u64 = 10;
+        let memo = b"invoice 42".to_vec();
         let check = SwapInstruction::Swap(Swap {
             amount_in,
             minimum_amount_out,
+            recipient: None,
+            slippage_bps: None,
+            max_price_impact_bps: None,
+            allow_partial: false,
+            max_owner_fee_pool_tokens: None,
+            clamp_owner_fee: false,
+            slippage_grace: 0,
+            quoted_amount_out: None,
+            memo: memo.clone(),
         });
         let packed = check.pack();
         let mut expect = vec![1];
         expect.extend_from_slice(&amount_in.to_le_bytes());
         expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.push(0);
+        expect.extend_from_slice(&0u64.to_le_bytes());
+        expect.push(0);
+        expect.extend_from_slice(&memo);
         assert_eq!(packed, expect);
         let unpacked = SwapInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
@@ -728,6 +3218,7 @@ mod tests {
             pool_token_amount,
             minimum_token_a_amount,
             minimum_token_b_amount,
+            unwrap_sol: false,
         });
         let packed = check.pack();
         let mut expect = vec![3];
@@ -737,6 +3228,18 @@ mod tests {
         assert_eq!(packed, expect);
         let unpacked = SwapInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
+
+        let check = SwapInstruction::WithdrawAllTokenTypes(WithdrawAllTokenTypes {
+            pool_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+            unwrap_sol: true,
+        });
+        let packed = check.pack();
+        expect.push(1);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
     }
 
     #[test]
@@ -766,6 +3269,7 @@ mod tests {
             WithdrawSingleTokenTypeExactAmountOut {
                 destination_token_amount,
                 maximum_pool_token_amount,
+                unwrap_sol: false,
             },
         );
         let packed = check.pack();
@@ -775,5 +3279,313 @@ mod tests {
         assert_eq!(packed, expect);
         let unpacked = SwapInstruction::unpack(&expect).unwrap();
         assert_eq!(unpacked, check);
+
+        let check = SwapInstruction::WithdrawSingleTokenTypeExactAmountOut(
+            WithdrawSingleTokenTypeExactAmountOut {
+                destination_token_amount,
+                maximum_pool_token_amount,
+                unwrap_sol: true,
+            },
+        );
+        let packed = check.pack();
+        expect.push(1);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_deposit_all_proportional() {
+        let maximum_token_a_amount: u64 = 10;
+        let maximum_token_b_amount: u64 = 20;
+        let minimum_pool_token_amount: u64 = 5;
+        let check = SwapInstruction::DepositAllProportional(DepositAllProportional {
+            maximum_token_a_amount,
+            maximum_token_b_amount,
+            minimum_pool_token_amount,
+        });
+        let packed = check.pack();
+        let mut expect = vec![7];
+        expect.extend_from_slice(&maximum_token_a_amount.to_le_bytes());
+        expect.extend_from_slice(&maximum_token_b_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_pool_token_amount.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_emergency_withdraw_proportional() {
+        let pool_token_amount: u64 = 1212438012089;
+        let minimum_token_a_amount: u64 = 102198761982612;
+        let minimum_token_b_amount: u64 = 2011239855213;
+        let check = SwapInstruction::EmergencyWithdrawProportional(EmergencyWithdrawProportional {
+            pool_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+        });
+        let packed = check.pack();
+        let mut expect = vec![8];
+        expect.extend_from_slice(&pool_token_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_token_a_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_token_b_amount.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_deposit_single_auto_balance() {
+        let source_token_amount: u64 = 10;
+        let minimum_pool_token_amount: u64 = 5;
+        let max_internal_impact_bps: u16 = 150;
+        let check = SwapInstruction::DepositSingleAutoBalance(DepositSingleAutoBalance {
+            source_token_amount,
+            minimum_pool_token_amount,
+            max_internal_impact_bps: Some(max_internal_impact_bps),
+        });
+        let packed = check.pack();
+        let mut expect = vec![9];
+        expect.extend_from_slice(&source_token_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_pool_token_amount.to_le_bytes());
+        expect.push(1);
+        expect.extend_from_slice(&max_internal_impact_bps.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // truncated impact cap
+        expect.pop();
+        assert_eq!(
+            SwapInstruction::unpack(&expect),
+            Err(SwapError::InvalidInstruction.into())
+        );
+
+        // data from older clients omits the impact cap
+        expect.truncate(expect.len() - 2);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(
+            unpacked,
+            SwapInstruction::DepositSingleAutoBalance(DepositSingleAutoBalance {
+                source_token_amount,
+                minimum_pool_token_amount,
+                max_internal_impact_bps: None,
+            })
+        );
+    }
+
+    #[test]
+    fn pack_get_implied_price_deviation() {
+        let reference_price: u128 = 1_500_000_000_000;
+        let check =
+            SwapInstruction::GetImpliedPriceDeviation(GetImpliedPriceDeviation { reference_price });
+        let packed = check.pack();
+        let mut expect = vec![10];
+        expect.extend_from_slice(&reference_price.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_skim() {
+        let check = SwapInstruction::Skim;
+        let packed = check.pack();
+        let expect = vec![6];
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_quote_fees() {
+        let amount_in: u64 = 1_000_000;
+        for (trade_direction, direction_byte) in
+            [(TradeDirection::AtoB, 0u8), (TradeDirection::BtoA, 1u8)]
+        {
+            let check = SwapInstruction::QuoteFees(QuoteFees {
+                amount_in,
+                trade_direction,
+            });
+            let packed = check.pack();
+            let mut expect = vec![13];
+            expect.extend_from_slice(&amount_in.to_le_bytes());
+            expect.push(direction_byte);
+            assert_eq!(packed, expect);
+            let unpacked = SwapInstruction::unpack(&expect).unwrap();
+            assert_eq!(unpacked, check);
+        }
+
+        // the direction is required and must be known
+        let mut data = vec![13];
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        assert_eq!(
+            SwapInstruction::unpack(&data),
+            Err(SwapError::InvalidInstruction.into())
+        );
+        data.push(2);
+        assert_eq!(
+            SwapInstruction::unpack(&data),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_verify_supply() {
+        let check = SwapInstruction::VerifySupply;
+        let packed = check.pack();
+        let expect = vec![12];
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_recover_tokens() {
+        let check = SwapInstruction::RecoverTokens;
+        let packed = check.pack();
+        let expect = vec![14];
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_redeem_owner_fees() {
+        let pool_token_amount: u64 = 1212438012089;
+        let minimum_token_a_amount: u64 = 102198761982612;
+        let minimum_token_b_amount: u64 = 2011239855213;
+        let check = SwapInstruction::RedeemOwnerFees(RedeemOwnerFees {
+            pool_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+        });
+        let packed = check.pack();
+        let mut expect = vec![15];
+        expect.extend_from_slice(&pool_token_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_token_a_amount.to_le_bytes());
+        expect.extend_from_slice(&minimum_token_b_amount.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_swap_split() {
+        let amount_in: u64 = 1_000_000;
+        let minimum_amount_out: u64 = 990_000;
+        let first_destination_bps: u16 = 2_500;
+        let check = SwapInstruction::SwapSplit(SwapSplit {
+            amount_in,
+            minimum_amount_out,
+            first_destination_bps,
+        });
+        let packed = check.pack();
+        let mut expect = vec![16];
+        expect.extend_from_slice(&amount_in.to_le_bytes());
+        expect.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        expect.extend_from_slice(&first_destination_bps.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+        assert_eq!(
+            SwapInstruction::unpack(&expect[..expect.len() - 1]),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_set_fee_account() {
+        let check = SwapInstruction::SetFeeAccount;
+        let packed = check.pack();
+        let expect = vec![17];
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_deposit_and_cpi() {
+        let pool_token_amount: u64 = 5;
+        let maximum_token_a_amount: u64 = 10;
+        let maximum_token_b_amount: u64 = 20;
+        let cpi_data = vec![7, 8, 9];
+        let check = SwapInstruction::DepositAndCpi(DepositAndCpi {
+            pool_token_amount,
+            maximum_token_a_amount,
+            maximum_token_b_amount,
+            cpi_data: cpi_data.clone(),
+        });
+        let packed = check.pack();
+        let mut expect = vec![19];
+        expect.extend_from_slice(&pool_token_amount.to_le_bytes());
+        expect.extend_from_slice(&maximum_token_a_amount.to_le_bytes());
+        expect.extend_from_slice(&maximum_token_b_amount.to_le_bytes());
+        expect.extend_from_slice(&cpi_data);
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // the target may take no data
+        let unpacked = SwapInstruction::unpack(&expect[..25]).unwrap();
+        assert_eq!(
+            unpacked,
+            SwapInstruction::DepositAndCpi(DepositAndCpi {
+                pool_token_amount,
+                maximum_token_a_amount,
+                maximum_token_b_amount,
+                cpi_data: vec![],
+            })
+        );
+        assert_eq!(
+            SwapInstruction::unpack(&expect[..24]),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_get_invariant() {
+        let check = SwapInstruction::GetInvariant;
+        let packed = check.pack();
+        let expect = vec![18];
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+
+    #[test]
+    fn pack_quote_withdraw_all() {
+        let pool_token_amount: u64 = 1_000_000;
+        let check = SwapInstruction::QuoteWithdrawAll(QuoteWithdrawAll { pool_token_amount });
+        let packed = check.pack();
+        let mut expect = vec![20];
+        expect.extend_from_slice(&pool_token_amount.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // the amount is required
+        assert_eq!(
+            SwapInstruction::unpack(&expect[..8]),
+            Err(SwapError::InvalidInstruction.into())
+        );
+    }
+
+    #[test]
+    fn pack_set_config() {
+        let owner = Pubkey::new_unique();
+        let check = SwapInstruction::SetConfig(SetConfig { owner });
+        let packed = check.pack();
+        let mut expect = vec![21];
+        expect.extend_from_slice(owner.as_ref());
+        assert_eq!(packed, expect);
+        let unpacked = SwapInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+
+        // the owner is required
+        assert_eq!(
+            SwapInstruction::unpack(&expect[..32]),
+            Err(SwapError::InvalidInstruction.into())
+        );
     }
 }
@@ -3,18 +3,30 @@
 
 //! An Uniswap-like program for the Solana blockchain.
 
+#[cfg(feature = "program")]
 pub mod constraints;
 pub mod curve;
+#[cfg(feature = "program")]
 pub mod error;
+#[cfg(feature = "program")]
 pub mod instruction;
+#[cfg(feature = "program")]
 pub mod processor;
+#[cfg(feature = "program")]
 pub mod state;
 
-#[cfg(not(feature = "no-entrypoint"))]
+#[cfg(all(feature = "program", not(feature = "no-entrypoint")))]
 mod entrypoint;
 
+// Quote math on plain integers, for clients. Without the default `program`
+// feature, this is all the crate builds, with no dependencies.
+#[cfg(feature = "client")]
+pub use curve::math as client;
+
 // Export current sdk types for downstream users building with a different sdk
 // version
+#[cfg(feature = "program")]
 pub use solana_program;
 
+#[cfg(feature = "program")]
 solana_program::declare_id!("SwapsVeCiPHMUAtzQWZw7RjsKjgCjhwU55QGu4U1Szw");
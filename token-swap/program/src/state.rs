@@ -1,14 +1,16 @@
 //! State transition types
 
+pub use crate::curve::math::TradeFees;
 use {
     crate::{
-        curve::{base::SwapCurve, fees::Fees},
+        curve::{base::SwapCurve, calculator::TradeDirection, fees::Fees},
         error::SwapError,
     },
     arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs},
     enum_dispatch::enum_dispatch,
     solana_program::{
         account_info::AccountInfo,
+        clock::UnixTimestamp,
         msg,
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack, Sealed},
@@ -18,9 +20,593 @@ use {
         extension::StateWithExtensions,
         state::{Account, AccountState},
     },
-    std::sync::Arc,
+    std::{convert::TryFrom, sync::Arc},
 };
 
+/// How the owner's share of trading fees is collected.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FeeMode {
+    /// The owner fee is converted to pool tokens and minted to the pool fee
+    /// account (and host fee account, if provided).
+    #[default]
+    MintPoolTokens,
+    /// The owner fee is left in the pool reserves and no pool tokens are
+    /// minted, so the full fee accrues to liquidity providers.
+    RetainInPool,
+}
+
+impl TryFrom<u8> for FeeMode {
+    type Error = ProgramError;
+
+    fn try_from(fee_mode: u8) -> Result<Self, Self::Error> {
+        match fee_mode {
+            0 => Ok(FeeMode::MintPoolTokens),
+            1 => Ok(FeeMode::RetainInPool),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// What the host fee fraction is taken of.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HostFeeBase {
+    /// The host fee is a fraction of the owner fee.
+    #[default]
+    OwnerFee,
+    /// The host fee is a fraction of the whole trading fee, owner fee
+    /// included, and is paid out of the owner fee.
+    TradeFee,
+}
+
+impl TryFrom<u8> for HostFeeBase {
+    type Error = ProgramError;
+
+    fn try_from(host_fee_base: u8) -> Result<Self, Self::Error> {
+        match host_fee_base {
+            0 => Ok(HostFeeBase::OwnerFee),
+            1 => Ok(HostFeeBase::TradeFee),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// A governance token balance and the trade fee discount it unlocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeDiscountTier {
+    /// Minimum governance token balance for the tier, zero disables it
+    pub threshold: u64,
+    /// Discount on trading fees, in basis points
+    pub discount_bps: u16,
+}
+
+/// Trading fee discounts for swappers holding a governance token.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeeDiscount {
+    /// Mint of the governance token, the default pubkey disables discounts
+    pub governance_mint: Pubkey,
+    /// Discount tiers, in any order
+    pub tiers: [FeeDiscountTier; FeeDiscount::MAX_TIERS],
+}
+
+impl FeeDiscount {
+    /// Number of tiers stored with each swap
+    pub const MAX_TIERS: usize = 3;
+
+    /// Whether a governance mint is configured
+    pub fn is_enabled(&self) -> bool {
+        self.governance_mint != Pubkey::default()
+    }
+
+    /// Discount for a governance token balance: the largest among the tiers
+    /// whose threshold the balance reaches
+    pub fn discount_bps(&self, balance: u64) -> u16 {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.threshold > 0 && balance >= tier.threshold)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Check that no tier discounts more than the whole fee
+    pub fn validate(&self) -> Result<(), SwapError> {
+        if self
+            .tiers
+            .iter()
+            .any(|tier| tier.discount_bps > MAX_DISCOUNT_BPS)
+        {
+            Err(SwapError::InvalidFee)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+const MAX_DISCOUNT_BPS: u16 = 10_000;
+
+impl Sealed for FeeDiscount {}
+impl IsInitialized for FeeDiscount {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for FeeDiscount {
+    const LEN: usize = 62;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 62];
+        let (governance_mint, tiers) = mut_array_refs![output, 32, 30];
+        governance_mint.copy_from_slice(self.governance_mint.as_ref());
+        for (tier, output) in self.tiers.iter().zip(tiers.chunks_exact_mut(10)) {
+            output[..8].copy_from_slice(&tier.threshold.to_le_bytes());
+            output[8..].copy_from_slice(&tier.discount_bps.to_le_bytes());
+        }
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 62];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (governance_mint, tiers_data) = array_refs![input, 32, 30];
+        let mut tiers = [FeeDiscountTier::default(); FeeDiscount::MAX_TIERS];
+        for (tier, input) in tiers.iter_mut().zip(tiers_data.chunks_exact(10)) {
+            let (threshold, discount_bps) = array_refs![array_ref![input, 0, 10], 8, 2];
+            tier.threshold = u64::from_le_bytes(*threshold);
+            tier.discount_bps = u16::from_le_bytes(*discount_bps);
+        }
+        Ok(Self {
+            governance_mint: Pubkey::new_from_array(*governance_mint),
+            tiers,
+        })
+    }
+}
+
+/// Weights for splitting the host fee of a swap across several host fee
+/// accounts, e.g. for multi-party referral programs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HostFeeSplit {
+    /// Weight of each host fee account, in the order they are passed to the
+    /// swap; the first zero weight ends the list
+    pub weights: [u16; HostFeeSplit::MAX_RECIPIENTS],
+}
+
+impl HostFeeSplit {
+    /// Number of host fee accounts a swap can split the host fee across
+    pub const MAX_RECIPIENTS: usize = 4;
+
+    /// Whether any recipient weights are configured
+    pub fn is_enabled(&self) -> bool {
+        self.weights[0] > 0
+    }
+
+    /// Number of host fee accounts the swap expects
+    pub fn recipients(&self) -> usize {
+        self.weights
+            .iter()
+            .take_while(|weight| **weight > 0)
+            .count()
+    }
+
+    /// Splits the host fee by weight. The rounding remainder goes to the
+    /// first recipient, which takes everything if no weights are configured.
+    pub fn shares(&self, host_fee: u128) -> Option<[u128; HostFeeSplit::MAX_RECIPIENTS]> {
+        let mut shares = [0; HostFeeSplit::MAX_RECIPIENTS];
+        let total_weight = self
+            .weights
+            .iter()
+            .map(|weight| u128::from(*weight))
+            .sum::<u128>();
+        if total_weight == 0 {
+            shares[0] = host_fee;
+            return Some(shares);
+        }
+        for (share, weight) in shares.iter_mut().zip(self.weights) {
+            *share = host_fee
+                .checked_mul(u128::from(weight))?
+                .checked_div(total_weight)?;
+        }
+        let distributed = shares
+            .iter()
+            .try_fold(0u128, |total, share| total.checked_add(*share))?;
+        shares[0] = shares[0].checked_add(host_fee.checked_sub(distributed)?)?;
+        Some(shares)
+    }
+
+    /// Check that no weight follows a zero weight
+    pub fn validate(&self) -> Result<(), SwapError> {
+        if self.weights[self.recipients()..]
+            .iter()
+            .any(|weight| *weight > 0)
+        {
+            Err(SwapError::InvalidFee)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Sealed for HostFeeSplit {}
+impl IsInitialized for HostFeeSplit {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for HostFeeSplit {
+    const LEN: usize = 8;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 8];
+        for (weight, output) in self.weights.iter().zip(output.chunks_exact_mut(2)) {
+            output.copy_from_slice(&weight.to_le_bytes());
+        }
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 8];
+        let mut weights = [0; HostFeeSplit::MAX_RECIPIENTS];
+        for (weight, input) in weights.iter_mut().zip(input.chunks_exact(2)) {
+            *weight = u16::from_le_bytes([input[0], input[1]]);
+        }
+        Ok(Self { weights })
+    }
+}
+
+/// Where pool tokens minted as owner fee go once the pool fee account holds
+/// more than a threshold, to keep fees from piling up in one account.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeSweep {
+    /// Pool fee account balance above which owner fees are swept, zero
+    /// disables sweeping
+    pub threshold: u64,
+    /// Pool token account receiving the owner fees beyond the threshold
+    pub destination: Pubkey,
+}
+
+impl FeeSweep {
+    /// Whether a sweep threshold is configured
+    pub fn is_enabled(&self) -> bool {
+        self.threshold > 0
+    }
+
+    /// Splits an owner fee between the pool fee account, up to the
+    /// threshold, and the sweep destination, given the pool fee account's
+    /// balance. Returns `(kept, swept)`.
+    pub fn split(&self, pool_fee_balance: u64, owner_fee: u128) -> (u128, u128) {
+        let room = u128::from(self.threshold.saturating_sub(pool_fee_balance));
+        let kept = owner_fee.min(room);
+        (kept, owner_fee - kept)
+    }
+
+    /// Check that a destination is set when sweeping is enabled
+    pub fn validate(&self) -> Result<(), SwapError> {
+        if self.is_enabled() && self.destination == Pubkey::default() {
+            Err(SwapError::InvalidFeeAccount)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Sealed for FeeSweep {}
+impl IsInitialized for FeeSweep {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for FeeSweep {
+    const LEN: usize = 40;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 40];
+        let (threshold, destination) = mut_array_refs![output, 8, 32];
+        *threshold = self.threshold.to_le_bytes();
+        destination.copy_from_slice(self.destination.as_ref());
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 40];
+        let (threshold, destination) = array_refs![input, 8, 32];
+        Ok(Self {
+            threshold: u64::from_le_bytes(*threshold),
+            destination: Pubkey::new_from_array(*destination),
+        })
+    }
+}
+
+/// Trading fees overridden for one swap direction or both, e.g. to
+/// discourage trading one way.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DirectionalFees {
+    /// Fees for swapping token A for token B, if not the pool's
+    pub a_to_b: Option<TradeFees>,
+    /// Fees for swapping token B for token A, if not the pool's
+    pub b_to_a: Option<TradeFees>,
+}
+
+impl DirectionalFees {
+    /// Whether either direction has its own fees
+    pub fn is_enabled(&self) -> bool {
+        self.a_to_b.is_some() || self.b_to_a.is_some()
+    }
+
+    /// The pool's fees with the trading fees for `trade_direction` applied
+    pub fn fees(&self, fees: &Fees, trade_direction: TradeDirection) -> Fees {
+        let trade_fees = match trade_direction {
+            TradeDirection::AtoB => self.a_to_b,
+            TradeDirection::BtoA => self.b_to_a,
+        };
+        match trade_fees {
+            Some(trade_fees) => Fees {
+                trade_fee_numerator: trade_fees.trade_fee_numerator,
+                trade_fee_denominator: trade_fees.trade_fee_denominator,
+                owner_trade_fee_numerator: trade_fees.owner_trade_fee_numerator,
+                owner_trade_fee_denominator: trade_fees.owner_trade_fee_denominator,
+                ..fees.clone()
+            },
+            None => fees.clone(),
+        }
+    }
+
+    /// Check that the fees are valid in both directions
+    pub fn validate(&self, fees: &Fees) -> Result<(), SwapError> {
+        self.fees(fees, TradeDirection::AtoB).validate()?;
+        self.fees(fees, TradeDirection::BtoA).validate()
+    }
+}
+
+impl Sealed for DirectionalFees {}
+impl IsInitialized for DirectionalFees {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for DirectionalFees {
+    const LEN: usize = 66;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 66];
+        let (a_to_b, b_to_a) = mut_array_refs![output, 33, 33];
+        for (trade_fees, output) in [(self.a_to_b, a_to_b), (self.b_to_a, b_to_a)] {
+            let (tag, values) = mut_array_refs![output, 1, 32];
+            let (
+                trade_fee_numerator,
+                trade_fee_denominator,
+                owner_trade_fee_numerator,
+                owner_trade_fee_denominator,
+            ) = mut_array_refs![values, 8, 8, 8, 8];
+            let trade_fees = match trade_fees {
+                Some(trade_fees) => {
+                    tag[0] = 1;
+                    trade_fees
+                }
+                None => {
+                    tag[0] = 0;
+                    TradeFees::default()
+                }
+            };
+            *trade_fee_numerator = trade_fees.trade_fee_numerator.to_le_bytes();
+            *trade_fee_denominator = trade_fees.trade_fee_denominator.to_le_bytes();
+            *owner_trade_fee_numerator = trade_fees.owner_trade_fee_numerator.to_le_bytes();
+            *owner_trade_fee_denominator = trade_fees.owner_trade_fee_denominator.to_le_bytes();
+        }
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 66];
+        let (a_to_b, b_to_a) = array_refs![input, 33, 33];
+        let unpack = |input: &[u8; 33]| {
+            let (tag, values) = array_refs![input, 1, 32];
+            let (
+                trade_fee_numerator,
+                trade_fee_denominator,
+                owner_trade_fee_numerator,
+                owner_trade_fee_denominator,
+            ) = array_refs![values, 8, 8, 8, 8];
+            match tag {
+                [0] => Ok(None),
+                [1] => Ok(Some(TradeFees {
+                    trade_fee_numerator: u64::from_le_bytes(*trade_fee_numerator),
+                    trade_fee_denominator: u64::from_le_bytes(*trade_fee_denominator),
+                    owner_trade_fee_numerator: u64::from_le_bytes(*owner_trade_fee_numerator),
+                    owner_trade_fee_denominator: u64::from_le_bytes(*owner_trade_fee_denominator),
+                })),
+                _ => Err(ProgramError::InvalidAccountData),
+            }
+        };
+        Ok(Self {
+            a_to_b: unpack(a_to_b)?,
+            b_to_a: unpack(b_to_a)?,
+        })
+    }
+}
+
+/// Trade fee that starts elevated when a pool launches and decays linearly
+/// to a floor, to reward bootstrapping liquidity.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LaunchFee {
+    /// Trade fee at launch, in basis points, zero disables the launch fee
+    pub launch_fee_bps: u16,
+    /// Trade fee once the decay window is over, in basis points
+    pub floor_fee_bps: u16,
+    /// Unix timestamp at which the decay starts
+    pub launch_time: UnixTimestamp,
+    /// Length of the decay window in seconds
+    pub decay_secs: u64,
+}
+
+impl LaunchFee {
+    /// Whether a launch fee is configured
+    pub fn is_enabled(&self) -> bool {
+        self.launch_fee_bps > 0
+    }
+
+    /// Trade fee in basis points at `unix_timestamp`, interpolated between
+    /// the launch fee and the floor
+    pub fn fee_bps(&self, unix_timestamp: UnixTimestamp) -> u16 {
+        // 上线前按上线费率收取
+        let elapsed = u64::try_from(unix_timestamp.saturating_sub(self.launch_time)).unwrap_or(0);
+        if elapsed >= self.decay_secs {
+            return self.floor_fee_bps;
+        }
+        let decay = u64::from(self.launch_fee_bps.saturating_sub(self.floor_fee_bps))
+            .saturating_mul(elapsed)
+            / self.decay_secs;
+        self.launch_fee_bps
+            .saturating_sub(u16::try_from(decay).unwrap_or(u16::MAX))
+    }
+
+    /// The pool's fees with the trade fee at `unix_timestamp` in place of the
+    /// pool's trade fee
+    pub fn fees(&self, fees: &Fees, unix_timestamp: UnixTimestamp) -> Fees {
+        Fees {
+            trade_fee_numerator: u64::from(self.fee_bps(unix_timestamp)),
+            trade_fee_denominator: 10_000,
+            ..fees.clone()
+        }
+    }
+
+    /// Check that the fee decays from at most 100% to the floor over a
+    /// non-empty window
+    pub fn validate(&self) -> Result<(), SwapError> {
+        if self.is_enabled()
+            && (self.launch_fee_bps > 10_000
+                || self.floor_fee_bps > self.launch_fee_bps
+                || self.decay_secs == 0)
+        {
+            Err(SwapError::InvalidFee)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Sealed for LaunchFee {}
+impl IsInitialized for LaunchFee {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+}
+
+impl Pack for LaunchFee {
+    const LEN: usize = 20;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 20];
+        let (launch_fee_bps, floor_fee_bps, launch_time, decay_secs) =
+            mut_array_refs![output, 2, 2, 8, 8];
+        *launch_fee_bps = self.launch_fee_bps.to_le_bytes();
+        *floor_fee_bps = self.floor_fee_bps.to_le_bytes();
+        *launch_time = self.launch_time.to_le_bytes();
+        *decay_secs = self.decay_secs.to_le_bytes();
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 20];
+        let (launch_fee_bps, floor_fee_bps, launch_time, decay_secs) =
+            array_refs![input, 2, 2, 8, 8];
+        Ok(Self {
+            launch_fee_bps: u16::from_le_bytes(*launch_fee_bps),
+            floor_fee_bps: u16::from_le_bytes(*floor_fee_bps),
+            launch_time: UnixTimestamp::from_le_bytes(*launch_time),
+            decay_secs: u64::from_le_bytes(*decay_secs),
+        })
+    }
+}
+
+/// Time of a liquidity provider's last deposit into a swap, used to enforce
+/// the swap's withdraw cooldown.
+///
+/// Kept in the program address derived from
+/// `[swap address, owner of the pool token account, "deposit"]`, see
+/// `Processor::find_deposit_record_address`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DepositRecord {
+    /// Initialized state
+    pub is_initialized: bool,
+    /// Unix timestamp of the last deposit
+    pub last_deposit_timestamp: UnixTimestamp,
+}
+
+impl Sealed for DepositRecord {}
+impl IsInitialized for DepositRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for DepositRecord {
+    const LEN: usize = 9;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 9];
+        let (is_initialized, last_deposit_timestamp) = mut_array_refs![output, 1, 8];
+        is_initialized[0] = self.is_initialized as u8;
+        *last_deposit_timestamp = self.last_deposit_timestamp.to_le_bytes();
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 9];
+        let (is_initialized, last_deposit_timestamp) = array_refs![input, 1, 8];
+        Ok(Self {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            last_deposit_timestamp: UnixTimestamp::from_le_bytes(*last_deposit_timestamp),
+        })
+    }
+}
+
+/// Program-wide settings, overriding the owner in `SWAP_CONSTRAINTS` for
+/// owner-gated instructions on swaps that don't set their own owner.
+///
+/// Kept in the program address derived from `["config"]`, see
+/// `Processor::find_config_address`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProgramConfig {
+    /// Initialized state
+    pub is_initialized: bool,
+    /// Key allowed to approve owner-gated instructions
+    pub owner: Pubkey,
+}
+
+impl Sealed for ProgramConfig {}
+impl IsInitialized for ProgramConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ProgramConfig {
+    const LEN: usize = 33;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 33];
+        let (is_initialized, owner) = mut_array_refs![output, 1, 32];
+        is_initialized[0] = self.is_initialized as u8;
+        owner.copy_from_slice(self.owner.as_ref());
+    }
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 33];
+        let (is_initialized, owner) = array_refs![input, 1, 32];
+        Ok(Self {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            owner: Pubkey::new_from_array(*owner),
+        })
+    }
+}
+
 /// Trait representing access to program state across all versions
 #[enum_dispatch]
 pub trait SwapState {
@@ -52,13 +638,59 @@ pub trait SwapState {
     fn fees(&self) -> &Fees;
     /// Curve associated with swap
     fn swap_curve(&self) -> &SwapCurve;
+    /// How owner trading fees are collected
+    fn fee_mode(&self) -> FeeMode;
+    /// Governance token fee discounts, if configured
+    fn fee_discount(&self) -> Option<&FeeDiscount>;
+    /// Host fee split across several host fee accounts, if configured
+    fn host_fee_split(&self) -> Option<&HostFeeSplit>;
+    /// Seconds after a deposit before the depositor may withdraw, zero if
+    /// there is no cooldown
+    fn withdraw_cooldown_secs(&self) -> u64;
+    /// Token A and B reserve balances as of the last instruction that moved
+    /// them, if tracked
+    fn recorded_reserves(&self) -> Option<(u64, u64)>;
+    /// Whether swaps pay host fees to host fee accounts
+    fn host_fees_enabled(&self) -> bool;
+    /// Cap on the owner fee minted by a single swap, in basis points of the
+    /// pool token supply, zero if uncapped
+    fn max_owner_fee_bps(&self) -> u16;
+    /// Smallest amount a swap may take in, if any
+    fn min_trade_amount(&self) -> Option<u64>;
+    /// Sweeping of owner fees beyond a pool fee account balance, if
+    /// configured
+    fn fee_sweep(&self) -> Option<&FeeSweep>;
+    /// Pool token supply accounted for by the program's own mints and burns,
+    /// if tracked
+    fn accounted_pool_supply(&self) -> Option<u64>;
+    /// Smallest balance both reserves must hold for swaps, if any
+    fn min_reserve_for_swap(&self) -> Option<u64>;
+    /// Whether any of the pool's token accounts belongs to token-2022, so
+    /// that mint extensions such as transfer fees must be checked. Assumed
+    /// if not recorded.
+    fn uses_token_2022(&self) -> bool;
+    /// What the host fee fraction is taken of
+    fn host_fee_base(&self) -> HostFeeBase;
+    /// Trading fees overridden per swap direction, if configured
+    fn directional_fees(&self) -> Option<&DirectionalFees>;
+    /// Decaying trade fee after launch, if configured
+    fn launch_fee(&self) -> Option<&LaunchFee>;
+    /// Only key allowed to authorize swaps, if the pool is permissioned
+    fn allowed_caller(&self) -> Option<&Pubkey>;
+    /// Whether single-sided deposits are rejected
+    fn balanced_deposits_only(&self) -> bool;
+    /// Key allowed to approve owner-gated instructions, if set on the swap
+    fn owner(&self) -> Option<&Pubkey>;
 }
 
 /// All versions of SwapState
 #[enum_dispatch(SwapState)]
+#[allow(clippy::large_enum_variant)]
 pub enum SwapVersion {
-    /// Latest version, used for all new swaps
+    /// Original version, still supported for existing swaps
     SwapV1,
+    /// Latest version, used for all new swaps
+    SwapV2,
 }
 
 /// SwapVersion does not implement program_pack::Pack because there are size
@@ -66,7 +698,7 @@ pub enum SwapVersion {
 /// special implementations are provided here
 impl SwapVersion {
     /// Size of the latest version of the SwapState
-    pub const LATEST_LEN: usize = 1 + SwapV1::LEN; // add one for the version enum
+    pub const LATEST_LEN: usize = 1 + SwapV2::LEN; // add one for the version enum
 
     /// Pack a swap into a byte array, based on its version
     /// 方法的核心作用是：
@@ -84,6 +716,10 @@ impl SwapVersion {
                 dst[0] = 1;
                 SwapV1::pack(swap_info, &mut dst[1..])
             }
+            Self::SwapV2(swap_info) => {
+                dst[0] = 2;
+                SwapV2::pack(swap_info, &mut dst[1..])
+            }
         }
     }
 
@@ -95,6 +731,20 @@ impl SwapVersion {
             .ok_or(ProgramError::InvalidAccountData)?;
         match version {
             1 => Ok(Arc::new(SwapV1::unpack(rest)?)),
+            2 => Ok(Arc::new(SwapV2::unpack(rest)?)),
+            _ => Err(ProgramError::UninitializedAccount),
+        }
+    }
+
+    /// Unpack the swap account into its concrete version, for instructions
+    /// that update the swap state
+    pub fn unpack_version(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&version, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidAccountData)?;
+        match version {
+            1 => Ok(Self::SwapV1(SwapV1::unpack(rest)?)),
+            2 => Ok(Self::SwapV2(SwapV2::unpack(rest)?)),
             _ => Err(ProgramError::UninitializedAccount),
         }
     }
@@ -109,26 +759,286 @@ impl SwapVersion {
     }
 }
 
-/// Program states.
-/// SwapV1 结构体定义了 Solana AMM 交易池的核心状态：
-// 	1.	流动性池状态（is_initialized）
-// 	2.	PDA 计算参数（bump_seed）
-// 	3.	交易代币信息（token_a、token_b、token_a_mint、token_b_mint）
-// 	4.	流动性池代币（LP Token）管理（pool_mint）
-// 	5.	费用收取账户（pool_fee_account）
-// 	6.	交易费用结构（fees）
-// 	7.	流动性池交易曲线（swap_curve）
-// 这个结构体在 Solana Token Swap 过程中起到了 存储和管理整个 AMM 交易池的作用，并确保 交易安全性和一致性。
+/// Program states.
+/// SwapV1 结构体定义了 Solana AMM 交易池的核心状态：
+// 	1.	流动性池状态（is_initialized）
+// 	2.	PDA 计算参数（bump_seed）
+// 	3.	交易代币信息（token_a、token_b、token_a_mint、token_b_mint）
+// 	4.	流动性池代币（LP Token）管理（pool_mint）
+// 	5.	费用收取账户（pool_fee_account）
+// 	6.	交易费用结构（fees）
+// 	7.	流动性池交易曲线（swap_curve）
+// 这个结构体在 Solana Token Swap 过程中起到了 存储和管理整个 AMM 交易池的作用，并确保 交易安全性和一致性。
+#[repr(C)]
+#[derive(Debug, Default, PartialEq)]
+pub struct SwapV1 {
+    /// Initialized state.
+    pub is_initialized: bool,
+    /// Bump seed used in program address.
+    /// The program address is created deterministically with the bump seed,
+    /// swap program id, and swap account pubkey.  This program address has
+    /// authority over the swap's token A account, token B account, and pool
+    /// token mint.
+    pub bump_seed: u8,
+
+    /// Program ID of the tokens being exchanged.
+    pub token_program_id: Pubkey,
+
+    /// Token A
+    pub token_a: Pubkey,
+    /// Token B
+    pub token_b: Pubkey,
+
+    /// Pool tokens are issued when A or B tokens are deposited.
+    /// Pool tokens can be withdrawn back to the original A or B token.
+    pub pool_mint: Pubkey,
+
+    /// Mint information for token A
+    pub token_a_mint: Pubkey,
+    /// Mint information for token B
+    pub token_b_mint: Pubkey,
+
+    /// Pool token account to receive trading and / or withdrawal fees
+    pub pool_fee_account: Pubkey,
+
+    /// All fee information
+    pub fees: Fees,
+
+    /// Swap curve parameters, to be unpacked and used by the SwapCurve, which
+    /// calculates swaps, deposits, and withdrawals
+    pub swap_curve: SwapCurve,
+}
+
+impl SwapState for SwapV1 {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+
+    fn bump_seed(&self) -> u8 {
+        self.bump_seed
+    }
+
+    fn token_program_id(&self) -> &Pubkey {
+        &self.token_program_id
+    }
+
+    fn token_a_account(&self) -> &Pubkey {
+        &self.token_a
+    }
+
+    fn token_b_account(&self) -> &Pubkey {
+        &self.token_b
+    }
+
+    fn pool_mint(&self) -> &Pubkey {
+        &self.pool_mint
+    }
+
+    fn token_a_mint(&self) -> &Pubkey {
+        &self.token_a_mint
+    }
+
+    fn token_b_mint(&self) -> &Pubkey {
+        &self.token_b_mint
+    }
+
+    fn pool_fee_account(&self) -> &Pubkey {
+        &self.pool_fee_account
+    }
+
+    fn check_pool_fee_info(&self, pool_fee_info: &AccountInfo) -> Result<(), ProgramError> {
+        check_pool_fee_info(pool_fee_info, &self.token_program_id, &self.pool_mint)
+    }
+
+    fn fees(&self) -> &Fees {
+        &self.fees
+    }
+
+    fn swap_curve(&self) -> &SwapCurve {
+        &self.swap_curve
+    }
+
+    fn fee_mode(&self) -> FeeMode {
+        FeeMode::MintPoolTokens
+    }
+
+    fn fee_discount(&self) -> Option<&FeeDiscount> {
+        None
+    }
+
+    fn host_fee_split(&self) -> Option<&HostFeeSplit> {
+        None
+    }
+
+    fn withdraw_cooldown_secs(&self) -> u64 {
+        0
+    }
+
+    fn recorded_reserves(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    fn host_fees_enabled(&self) -> bool {
+        true
+    }
+
+    fn max_owner_fee_bps(&self) -> u16 {
+        0
+    }
+
+    fn min_trade_amount(&self) -> Option<u64> {
+        None
+    }
+
+    fn fee_sweep(&self) -> Option<&FeeSweep> {
+        None
+    }
+
+    fn accounted_pool_supply(&self) -> Option<u64> {
+        None
+    }
+
+    fn min_reserve_for_swap(&self) -> Option<u64> {
+        None
+    }
+
+    fn uses_token_2022(&self) -> bool {
+        true
+    }
+
+    fn host_fee_base(&self) -> HostFeeBase {
+        HostFeeBase::OwnerFee
+    }
+
+    fn directional_fees(&self) -> Option<&DirectionalFees> {
+        None
+    }
+
+    fn launch_fee(&self) -> Option<&LaunchFee> {
+        None
+    }
+
+    fn allowed_caller(&self) -> Option<&Pubkey> {
+        None
+    }
+
+    fn balanced_deposits_only(&self) -> bool {
+        false
+    }
+
+    fn owner(&self) -> Option<&Pubkey> {
+        None
+    }
+}
+
+/// Checks that the pool fee account is an initialized token account for the
+/// pool mint, owned by the pool token program.
+fn check_pool_fee_info(
+    pool_fee_info: &AccountInfo,
+    token_program_id: &Pubkey,
+    pool_mint: &Pubkey,
+) -> Result<(), ProgramError> {
+    let data = &pool_fee_info.data.borrow();
+    let token_account = StateWithExtensions::<Account>::unpack(data).map_err(|err| match err {
+        ProgramError::InvalidAccountData | ProgramError::UninitializedAccount => {
+            SwapError::InvalidFeeAccount.into()
+        }
+        _ => err,
+    })?;
+    if pool_fee_info.owner != token_program_id
+        || token_account.base.state != AccountState::Initialized
+        || token_account.base.mint != *pool_mint
+    {
+        msg!("Pool fee account is not owned by token program, is not initialized, or does not match stake pool's mint");
+        return Err(SwapError::InvalidFeeAccount.into());
+    }
+    Ok(())
+}
+
+impl Sealed for SwapV1 {}
+impl IsInitialized for SwapV1 {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for SwapV1 {
+    const LEN: usize = 323;
+
+    fn pack_into_slice(&self, output: &mut [u8]) {
+        let output = array_mut_ref![output, 0, 323];
+        let (
+            is_initialized,
+            bump_seed,
+            token_program_id,
+            token_a,
+            token_b,
+            pool_mint,
+            token_a_mint,
+            token_b_mint,
+            pool_fee_account,
+            fees,
+            swap_curve,
+        ) = mut_array_refs![output, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33];
+        is_initialized[0] = self.is_initialized as u8;
+        bump_seed[0] = self.bump_seed;
+        token_program_id.copy_from_slice(self.token_program_id.as_ref());
+        token_a.copy_from_slice(self.token_a.as_ref());
+        token_b.copy_from_slice(self.token_b.as_ref());
+        pool_mint.copy_from_slice(self.pool_mint.as_ref());
+        token_a_mint.copy_from_slice(self.token_a_mint.as_ref());
+        token_b_mint.copy_from_slice(self.token_b_mint.as_ref());
+        pool_fee_account.copy_from_slice(self.pool_fee_account.as_ref());
+        self.fees.pack_into_slice(&mut fees[..]);
+        self.swap_curve.pack_into_slice(&mut swap_curve[..]);
+    }
+
+    /// Unpacks a byte buffer into a [SwapV1](struct.SwapV1.html).
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, 323];
+        #[allow(clippy::ptr_offset_with_cast)]
+        let (
+            is_initialized,
+            bump_seed,
+            token_program_id,
+            token_a,
+            token_b,
+            pool_mint,
+            token_a_mint,
+            token_b_mint,
+            pool_fee_account,
+            fees,
+            swap_curve,
+        ) = array_refs![input, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33];
+        Ok(Self {
+            is_initialized: match is_initialized {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            bump_seed: bump_seed[0],
+            token_program_id: Pubkey::new_from_array(*token_program_id),
+            token_a: Pubkey::new_from_array(*token_a),
+            token_b: Pubkey::new_from_array(*token_b),
+            pool_mint: Pubkey::new_from_array(*pool_mint),
+            token_a_mint: Pubkey::new_from_array(*token_a_mint),
+            token_b_mint: Pubkey::new_from_array(*token_b_mint),
+            pool_fee_account: Pubkey::new_from_array(*pool_fee_account),
+            fees: Fees::unpack_from_slice(fees)?,
+            swap_curve: SwapCurve::unpack_from_slice(swap_curve)?,
+        })
+    }
+}
+
+/// Program states, version 2.
+/// Same layout as [SwapV1](struct.SwapV1.html), followed by per-pool
+/// settings chosen at initialization.
 #[repr(C)]
 #[derive(Debug, Default, PartialEq)]
-pub struct SwapV1 {
+pub struct SwapV2 {
     /// Initialized state.
     pub is_initialized: bool,
     /// Bump seed used in program address.
-    /// The program address is created deterministically with the bump seed,
-    /// swap program id, and swap account pubkey.  This program address has
-    /// authority over the swap's token A account, token B account, and pool
-    /// token mint.
     pub bump_seed: u8,
 
     /// Program ID of the tokens being exchanged.
@@ -157,9 +1067,94 @@ pub struct SwapV1 {
     /// Swap curve parameters, to be unpacked and used by the SwapCurve, which
     /// calculates swaps, deposits, and withdrawals
     pub swap_curve: SwapCurve,
+
+    /// How owner trading fees are collected
+    pub fee_mode: FeeMode,
+
+    /// Trading fee discounts for governance token holders
+    pub fee_discount: FeeDiscount,
+
+    /// Weights for splitting the host fee across several host fee accounts
+    pub host_fee_split: HostFeeSplit,
+
+    /// Seconds after a deposit before the depositor may withdraw
+    pub withdraw_cooldown_secs: u64,
+
+    /// Balance of the token A reserve account as of the last instruction
+    /// that moved it, anything above it was sent to the account directly
+    pub token_a_reserve: u64,
+    /// Balance of the token B reserve account as of the last instruction
+    /// that moved it
+    pub token_b_reserve: u64,
+
+    /// Whether swaps pay host fees, if not the whole owner fee goes to the
+    /// pool fee account
+    pub host_fees_enabled: bool,
+
+    /// Cap on the pool tokens minted as owner fee by a single swap, in basis
+    /// points of the pool token supply, zero for no cap
+    pub max_owner_fee_bps: u16,
+
+    /// Smallest amount a swap may take in, if any
+    pub min_trade_amount: Option<u64>,
+
+    /// Trading fees taken in token A by all swaps so far, saturating
+    pub cumulative_trade_fees_a: u64,
+    /// Trading fees taken in token B by all swaps so far, saturating
+    pub cumulative_trade_fees_b: u64,
+    /// Pool tokens minted as owner and host fees by all swaps so far,
+    /// saturating
+    pub cumulative_owner_fees: u64,
+
+    /// Sweeping of owner fees beyond a pool fee account balance
+    pub fee_sweep: FeeSweep,
+
+    /// Pool tokens minted by the program over the life of the pool,
+    /// wrapping
+    pub total_lp_minted: u64,
+    /// Pool tokens burned by the program over the life of the pool,
+    /// wrapping
+    pub total_lp_burned: u64,
+
+    /// Smallest balance both reserves must hold for swaps, if any
+    pub min_reserve_for_swap: Option<u64>,
+
+    /// Whether the pool mint or either reserve belongs to token-2022, set on
+    /// initialization
+    pub uses_token_2022: bool,
+
+    /// What the host fee fraction is taken of
+    pub host_fee_base: HostFeeBase,
+
+    /// Trading fees overridden per swap direction
+    pub directional_fees: DirectionalFees,
+
+    /// Slot in which the pool was initialized
+    pub created_at_slot: u64,
+    /// Unix timestamp at which the pool was initialized
+    pub created_at_timestamp: i64,
+
+    /// Decaying trade fee after launch
+    pub launch_fee: LaunchFee,
+
+    /// Only key allowed to authorize swaps, anyone if unset
+    pub allowed_caller: Option<Pubkey>,
+
+    /// Growth of the curve invariant kept by the pool when swap outputs
+    /// round in its favor, beyond what fees add, saturating. In the curve's
+    /// invariant units, e.g. token A times token B amounts for the constant
+    /// product curve, not a token amount.
+    pub invariant_rounding_dust: u128,
+
+    /// Whether single-sided deposits are rejected
+    pub balanced_deposits_only: bool,
+
+    /// Key allowed to approve owner-gated instructions, ahead of the program
+    /// config and the owner of the pool fee account
+    pub owner: Option<Pubkey>,
 }
 
-impl SwapState for SwapV1 {
+impl SwapState for SwapV2 {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
@@ -197,22 +1192,7 @@ impl SwapState for SwapV1 {
     }
 
     fn check_pool_fee_info(&self, pool_fee_info: &AccountInfo) -> Result<(), ProgramError> {
-        let data = &pool_fee_info.data.borrow();
-        let token_account =
-            StateWithExtensions::<Account>::unpack(data).map_err(|err| match err {
-                ProgramError::InvalidAccountData | ProgramError::UninitializedAccount => {
-                    SwapError::InvalidFeeAccount.into()
-                }
-                _ => err,
-            })?;
-        if pool_fee_info.owner != &self.token_program_id
-            || token_account.base.state != AccountState::Initialized
-            || token_account.base.mint != self.pool_mint
-        {
-            msg!("Pool fee account is not owned by token program, is not initialized, or does not match stake pool's mint");
-            return Err(SwapError::InvalidFeeAccount.into());
-        }
-        Ok(())
+        check_pool_fee_info(pool_fee_info, &self.token_program_id, &self.pool_mint)
     }
 
     fn fees(&self) -> &Fees {
@@ -222,20 +1202,92 @@ impl SwapState for SwapV1 {
     fn swap_curve(&self) -> &SwapCurve {
         &self.swap_curve
     }
+
+    fn fee_mode(&self) -> FeeMode {
+        self.fee_mode
+    }
+
+    fn fee_discount(&self) -> Option<&FeeDiscount> {
+        Some(&self.fee_discount).filter(|fee_discount| fee_discount.is_enabled())
+    }
+
+    fn host_fee_split(&self) -> Option<&HostFeeSplit> {
+        Some(&self.host_fee_split).filter(|host_fee_split| host_fee_split.is_enabled())
+    }
+
+    fn withdraw_cooldown_secs(&self) -> u64 {
+        self.withdraw_cooldown_secs
+    }
+
+    fn recorded_reserves(&self) -> Option<(u64, u64)> {
+        Some((self.token_a_reserve, self.token_b_reserve))
+    }
+
+    fn host_fees_enabled(&self) -> bool {
+        self.host_fees_enabled
+    }
+
+    fn max_owner_fee_bps(&self) -> u16 {
+        self.max_owner_fee_bps
+    }
+
+    fn min_trade_amount(&self) -> Option<u64> {
+        self.min_trade_amount
+    }
+
+    fn fee_sweep(&self) -> Option<&FeeSweep> {
+        Some(&self.fee_sweep).filter(|fee_sweep| fee_sweep.is_enabled())
+    }
+
+    fn accounted_pool_supply(&self) -> Option<u64> {
+        Some(self.total_lp_minted.wrapping_sub(self.total_lp_burned))
+    }
+
+    fn min_reserve_for_swap(&self) -> Option<u64> {
+        self.min_reserve_for_swap
+    }
+
+    fn uses_token_2022(&self) -> bool {
+        self.uses_token_2022
+    }
+
+    fn host_fee_base(&self) -> HostFeeBase {
+        self.host_fee_base
+    }
+
+    fn directional_fees(&self) -> Option<&DirectionalFees> {
+        Some(&self.directional_fees).filter(|directional_fees| directional_fees.is_enabled())
+    }
+
+    fn launch_fee(&self) -> Option<&LaunchFee> {
+        Some(&self.launch_fee).filter(|launch_fee| launch_fee.is_enabled())
+    }
+
+    fn allowed_caller(&self) -> Option<&Pubkey> {
+        self.allowed_caller.as_ref()
+    }
+
+    fn balanced_deposits_only(&self) -> bool {
+        self.balanced_deposits_only
+    }
+
+    fn owner(&self) -> Option<&Pubkey> {
+        self.owner.as_ref()
+    }
 }
 
-impl Sealed for SwapV1 {}
-impl IsInitialized for SwapV1 {
+impl Sealed for SwapV2 {}
+impl IsInitialized for SwapV2 {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
 }
 
-impl Pack for SwapV1 {
-    const LEN: usize = 323;
+impl Pack for SwapV2 {
+    const LEN: usize = 706;
 
     fn pack_into_slice(&self, output: &mut [u8]) {
-        let output = array_mut_ref![output, 0, 323];
+        let output = array_mut_ref![output, 0, 706];
         let (
             is_initialized,
             bump_seed,
@@ -248,7 +1300,36 @@ impl Pack for SwapV1 {
             pool_fee_account,
             fees,
             swap_curve,
-        ) = mut_array_refs![output, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33];
+            fee_mode,
+            fee_discount,
+            host_fee_split,
+            withdraw_cooldown_secs,
+            token_a_reserve,
+            token_b_reserve,
+            host_fees_enabled,
+            max_owner_fee_bps,
+            min_trade_amount,
+            cumulative_trade_fees_a,
+            cumulative_trade_fees_b,
+            cumulative_owner_fees,
+            fee_sweep,
+            total_lp_minted,
+            total_lp_burned,
+            min_reserve_for_swap,
+            uses_token_2022,
+            host_fee_base,
+            directional_fees,
+            created_at_slot,
+            created_at_timestamp,
+            launch_fee,
+            allowed_caller,
+            invariant_rounding_dust,
+            balanced_deposits_only,
+            owner,
+        ) = mut_array_refs![
+            output, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33, 1, 62, 8, 8, 8, 8, 1, 2, 9, 8, 8, 8,
+            40, 8, 8, 9, 1, 1, 66, 8, 8, 20, 33, 16, 1, 33
+        ];
         is_initialized[0] = self.is_initialized as u8;
         bump_seed[0] = self.bump_seed;
         token_program_id.copy_from_slice(self.token_program_id.as_ref());
@@ -260,11 +1341,80 @@ impl Pack for SwapV1 {
         pool_fee_account.copy_from_slice(self.pool_fee_account.as_ref());
         self.fees.pack_into_slice(&mut fees[..]);
         self.swap_curve.pack_into_slice(&mut swap_curve[..]);
+        fee_mode[0] = self.fee_mode as u8;
+        self.fee_discount.pack_into_slice(&mut fee_discount[..]);
+        self.host_fee_split.pack_into_slice(&mut host_fee_split[..]);
+        *withdraw_cooldown_secs = self.withdraw_cooldown_secs.to_le_bytes();
+        *token_a_reserve = self.token_a_reserve.to_le_bytes();
+        *token_b_reserve = self.token_b_reserve.to_le_bytes();
+        host_fees_enabled[0] = self.host_fees_enabled as u8;
+        *max_owner_fee_bps = self.max_owner_fee_bps.to_le_bytes();
+        let (min_trade_amount_tag, min_trade_amount_value) =
+            mut_array_refs![min_trade_amount, 1, 8];
+        match self.min_trade_amount {
+            Some(min_trade_amount) => {
+                min_trade_amount_tag[0] = 1;
+                *min_trade_amount_value = min_trade_amount.to_le_bytes();
+            }
+            None => {
+                min_trade_amount_tag[0] = 0;
+                *min_trade_amount_value = [0; 8];
+            }
+        }
+        *cumulative_trade_fees_a = self.cumulative_trade_fees_a.to_le_bytes();
+        *cumulative_trade_fees_b = self.cumulative_trade_fees_b.to_le_bytes();
+        *cumulative_owner_fees = self.cumulative_owner_fees.to_le_bytes();
+        self.fee_sweep.pack_into_slice(&mut fee_sweep[..]);
+        *total_lp_minted = self.total_lp_minted.to_le_bytes();
+        *total_lp_burned = self.total_lp_burned.to_le_bytes();
+        let (min_reserve_for_swap_tag, min_reserve_for_swap_value) =
+            mut_array_refs![min_reserve_for_swap, 1, 8];
+        match self.min_reserve_for_swap {
+            Some(min_reserve_for_swap) => {
+                min_reserve_for_swap_tag[0] = 1;
+                *min_reserve_for_swap_value = min_reserve_for_swap.to_le_bytes();
+            }
+            None => {
+                min_reserve_for_swap_tag[0] = 0;
+                *min_reserve_for_swap_value = [0; 8];
+            }
+        }
+        uses_token_2022[0] = self.uses_token_2022 as u8;
+        host_fee_base[0] = self.host_fee_base as u8;
+        self.directional_fees
+            .pack_into_slice(&mut directional_fees[..]);
+        *created_at_slot = self.created_at_slot.to_le_bytes();
+        *created_at_timestamp = self.created_at_timestamp.to_le_bytes();
+        self.launch_fee.pack_into_slice(&mut launch_fee[..]);
+        let (allowed_caller_tag, allowed_caller_key) = mut_array_refs![allowed_caller, 1, 32];
+        match self.allowed_caller {
+            Some(allowed_caller) => {
+                allowed_caller_tag[0] = 1;
+                allowed_caller_key.copy_from_slice(allowed_caller.as_ref());
+            }
+            None => {
+                allowed_caller_tag[0] = 0;
+                *allowed_caller_key = [0; 32];
+            }
+        }
+        *invariant_rounding_dust = self.invariant_rounding_dust.to_le_bytes();
+        balanced_deposits_only[0] = self.balanced_deposits_only as u8;
+        let (owner_tag, owner_key) = mut_array_refs![owner, 1, 32];
+        match self.owner {
+            Some(owner) => {
+                owner_tag[0] = 1;
+                owner_key.copy_from_slice(owner.as_ref());
+            }
+            None => {
+                owner_tag[0] = 0;
+                *owner_key = [0; 32];
+            }
+        }
     }
 
-    /// Unpacks a byte buffer into a [SwapV1](struct.SwapV1.html).
+    /// Unpacks a byte buffer into a [SwapV2](struct.SwapV2.html).
     fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
-        let input = array_ref![input, 0, 323];
+        let input = array_ref![input, 0, 706];
         #[allow(clippy::ptr_offset_with_cast)]
         let (
             is_initialized,
@@ -278,7 +1428,41 @@ impl Pack for SwapV1 {
             pool_fee_account,
             fees,
             swap_curve,
-        ) = array_refs![input, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33];
+            fee_mode,
+            fee_discount,
+            host_fee_split,
+            withdraw_cooldown_secs,
+            token_a_reserve,
+            token_b_reserve,
+            host_fees_enabled,
+            max_owner_fee_bps,
+            min_trade_amount,
+            cumulative_trade_fees_a,
+            cumulative_trade_fees_b,
+            cumulative_owner_fees,
+            fee_sweep,
+            total_lp_minted,
+            total_lp_burned,
+            min_reserve_for_swap,
+            uses_token_2022,
+            host_fee_base,
+            directional_fees,
+            created_at_slot,
+            created_at_timestamp,
+            launch_fee,
+            allowed_caller,
+            invariant_rounding_dust,
+            balanced_deposits_only,
+            owner,
+        ) = array_refs![
+            input, 1, 1, 32, 32, 32, 32, 32, 32, 32, 64, 33, 1, 62, 8, 8, 8, 8, 1, 2, 9, 8, 8, 8,
+            40, 8, 8, 9, 1, 1, 66, 8, 8, 20, 33, 16, 1, 33
+        ];
+        let (min_trade_amount_tag, min_trade_amount_value) = array_refs![min_trade_amount, 1, 8];
+        let (min_reserve_for_swap_tag, min_reserve_for_swap_value) =
+            array_refs![min_reserve_for_swap, 1, 8];
+        let (allowed_caller_tag, allowed_caller_key) = array_refs![allowed_caller, 1, 32];
+        let (owner_tag, owner_key) = array_refs![owner, 1, 32];
         Ok(Self {
             is_initialized: match is_initialized {
                 [0] => false,
@@ -295,6 +1479,60 @@ impl Pack for SwapV1 {
             pool_fee_account: Pubkey::new_from_array(*pool_fee_account),
             fees: Fees::unpack_from_slice(fees)?,
             swap_curve: SwapCurve::unpack_from_slice(swap_curve)?,
+            fee_mode: FeeMode::try_from(fee_mode[0])?,
+            fee_discount: FeeDiscount::unpack_from_slice(fee_discount)?,
+            host_fee_split: HostFeeSplit::unpack_from_slice(host_fee_split)?,
+            withdraw_cooldown_secs: u64::from_le_bytes(*withdraw_cooldown_secs),
+            token_a_reserve: u64::from_le_bytes(*token_a_reserve),
+            token_b_reserve: u64::from_le_bytes(*token_b_reserve),
+            host_fees_enabled: match host_fees_enabled {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            max_owner_fee_bps: u16::from_le_bytes(*max_owner_fee_bps),
+            min_trade_amount: match min_trade_amount_tag {
+                [0] => None,
+                [1] => Some(u64::from_le_bytes(*min_trade_amount_value)),
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            cumulative_trade_fees_a: u64::from_le_bytes(*cumulative_trade_fees_a),
+            cumulative_trade_fees_b: u64::from_le_bytes(*cumulative_trade_fees_b),
+            cumulative_owner_fees: u64::from_le_bytes(*cumulative_owner_fees),
+            fee_sweep: FeeSweep::unpack_from_slice(fee_sweep)?,
+            total_lp_minted: u64::from_le_bytes(*total_lp_minted),
+            total_lp_burned: u64::from_le_bytes(*total_lp_burned),
+            min_reserve_for_swap: match min_reserve_for_swap_tag {
+                [0] => None,
+                [1] => Some(u64::from_le_bytes(*min_reserve_for_swap_value)),
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            uses_token_2022: match uses_token_2022 {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            host_fee_base: HostFeeBase::try_from(host_fee_base[0])?,
+            directional_fees: DirectionalFees::unpack_from_slice(directional_fees)?,
+            created_at_slot: u64::from_le_bytes(*created_at_slot),
+            created_at_timestamp: i64::from_le_bytes(*created_at_timestamp),
+            launch_fee: LaunchFee::unpack_from_slice(launch_fee)?,
+            allowed_caller: match allowed_caller_tag {
+                [0] => None,
+                [1] => Some(Pubkey::new_from_array(*allowed_caller_key)),
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            invariant_rounding_dust: u128::from_le_bytes(*invariant_rounding_dust),
+            balanced_deposits_only: match balanced_deposits_only {
+                [0] => false,
+                [1] => true,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            owner: match owner_tag {
+                [0] => None,
+                [1] => Some(Pubkey::new_from_array(*owner_key)),
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
         })
     }
 }
@@ -323,6 +1561,70 @@ mod tests {
     const TEST_TOKEN_B_MINT: Pubkey = Pubkey::new_from_array([6u8; 32]);
     const TEST_POOL_FEE_ACCOUNT: Pubkey = Pubkey::new_from_array([7u8; 32]);
 
+    const TEST_GOVERNANCE_MINT: Pubkey = Pubkey::new_from_array([8u8; 32]);
+
+    fn test_fee_discount() -> FeeDiscount {
+        FeeDiscount {
+            governance_mint: TEST_GOVERNANCE_MINT,
+            tiers: [
+                FeeDiscountTier {
+                    threshold: 1_000,
+                    discount_bps: 2_500,
+                },
+                FeeDiscountTier {
+                    threshold: 10_000,
+                    discount_bps: 5_000,
+                },
+                FeeDiscountTier::default(),
+            ],
+        }
+    }
+
+    const TEST_HOST_FEE_SPLIT: HostFeeSplit = HostFeeSplit {
+        weights: [1, 3, 0, 0],
+    };
+
+    const TEST_WITHDRAW_COOLDOWN_SECS: u64 = 3_600;
+    const TEST_TOKEN_A_RESERVE: u64 = 1_000;
+    const TEST_TOKEN_B_RESERVE: u64 = 2_000;
+    const TEST_MAX_OWNER_FEE_BPS: u16 = 100;
+    const TEST_MIN_TRADE_AMOUNT: u64 = 10;
+    const TEST_CUMULATIVE_TRADE_FEES_A: u64 = 300;
+    const TEST_CUMULATIVE_TRADE_FEES_B: u64 = 700;
+    const TEST_CUMULATIVE_OWNER_FEES: u64 = 50;
+    const TEST_TOTAL_LP_MINTED: u64 = 1_000_000;
+    const TEST_TOTAL_LP_BURNED: u64 = 250_000;
+    const TEST_MIN_RESERVE_FOR_SWAP: u64 = 100;
+    const TEST_CREATED_AT_SLOT: u64 = 42;
+    const TEST_CREATED_AT_TIMESTAMP: i64 = 1_700_000_000;
+    const TEST_FEE_SWEEP: FeeSweep = FeeSweep {
+        threshold: 5_000,
+        destination: Pubkey::new_from_array([9u8; 32]),
+    };
+
+    const TEST_DIRECTIONAL_FEES: DirectionalFees = DirectionalFees {
+        a_to_b: None,
+        b_to_a: Some(TradeFees {
+            trade_fee_numerator: 30,
+            trade_fee_denominator: 10_000,
+            owner_trade_fee_numerator: 10,
+            owner_trade_fee_denominator: 10_000,
+        }),
+    };
+
+    const TEST_LAUNCH_FEE: LaunchFee = LaunchFee {
+        launch_fee_bps: 500,
+        floor_fee_bps: 30,
+        launch_time: TEST_CREATED_AT_TIMESTAMP,
+        decay_secs: 86_400,
+    };
+
+    const TEST_ALLOWED_CALLER: Pubkey = Pubkey::new_from_array([10u8; 32]);
+
+    const TEST_OWNER: Pubkey = Pubkey::new_from_array([11u8; 32]);
+
+    const TEST_INVARIANT_ROUNDING_DUST: u128 = 123_456_789_012_345;
+
     const TEST_CURVE_TYPE: u8 = 2;
     const TEST_TOKEN_B_OFFSET: u64 = 1_000_000_000;
     const TEST_CURVE: OffsetCurve = OffsetCurve {
@@ -351,8 +1653,75 @@ mod tests {
             swap_curve: swap_curve.clone(),
         });
 
+        let mut packed = [0u8; 1 + SwapV1::LEN];
+        SwapVersion::pack(swap_info, &mut packed).unwrap();
+        let unpacked = SwapVersion::unpack(&packed).unwrap();
+
+        assert!(unpacked.is_initialized());
+        assert_eq!(unpacked.bump_seed(), TEST_BUMP_SEED);
+        assert_eq!(*unpacked.token_program_id(), TEST_TOKEN_PROGRAM_ID);
+        assert_eq!(*unpacked.token_a_account(), TEST_TOKEN_A);
+        assert_eq!(*unpacked.token_b_account(), TEST_TOKEN_B);
+        assert_eq!(*unpacked.pool_mint(), TEST_POOL_MINT);
+        assert_eq!(*unpacked.token_a_mint(), TEST_TOKEN_A_MINT);
+        assert_eq!(*unpacked.token_b_mint(), TEST_TOKEN_B_MINT);
+        assert_eq!(*unpacked.pool_fee_account(), TEST_POOL_FEE_ACCOUNT);
+        assert_eq!(*unpacked.fees(), TEST_FEES);
+        assert_eq!(*unpacked.swap_curve(), swap_curve);
+        assert_eq!(unpacked.fee_mode(), FeeMode::MintPoolTokens);
+    }
+
+    #[test]
+    fn swap_version_pack_v2() {
+        let curve_type = TEST_CURVE_TYPE.try_into().unwrap();
+        let calculator = Arc::new(TEST_CURVE);
+        let swap_curve = SwapCurve {
+            curve_type,
+            calculator,
+        };
+        let swap_info = SwapVersion::SwapV2(SwapV2 {
+            is_initialized: true,
+            bump_seed: TEST_BUMP_SEED,
+            token_program_id: TEST_TOKEN_PROGRAM_ID,
+            token_a: TEST_TOKEN_A,
+            token_b: TEST_TOKEN_B,
+            pool_mint: TEST_POOL_MINT,
+            token_a_mint: TEST_TOKEN_A_MINT,
+            token_b_mint: TEST_TOKEN_B_MINT,
+            pool_fee_account: TEST_POOL_FEE_ACCOUNT,
+            fees: TEST_FEES,
+            swap_curve: swap_curve.clone(),
+            fee_mode: FeeMode::RetainInPool,
+            fee_discount: test_fee_discount(),
+            host_fee_split: TEST_HOST_FEE_SPLIT,
+            withdraw_cooldown_secs: TEST_WITHDRAW_COOLDOWN_SECS,
+            token_a_reserve: TEST_TOKEN_A_RESERVE,
+            token_b_reserve: TEST_TOKEN_B_RESERVE,
+            host_fees_enabled: false,
+            max_owner_fee_bps: TEST_MAX_OWNER_FEE_BPS,
+            min_trade_amount: Some(TEST_MIN_TRADE_AMOUNT),
+            cumulative_trade_fees_a: TEST_CUMULATIVE_TRADE_FEES_A,
+            cumulative_trade_fees_b: TEST_CUMULATIVE_TRADE_FEES_B,
+            cumulative_owner_fees: TEST_CUMULATIVE_OWNER_FEES,
+            fee_sweep: TEST_FEE_SWEEP,
+            total_lp_minted: TEST_TOTAL_LP_MINTED,
+            total_lp_burned: TEST_TOTAL_LP_BURNED,
+            min_reserve_for_swap: Some(TEST_MIN_RESERVE_FOR_SWAP),
+            uses_token_2022: true,
+            host_fee_base: HostFeeBase::TradeFee,
+            directional_fees: TEST_DIRECTIONAL_FEES,
+            created_at_slot: TEST_CREATED_AT_SLOT,
+            created_at_timestamp: TEST_CREATED_AT_TIMESTAMP,
+            launch_fee: TEST_LAUNCH_FEE,
+            allowed_caller: Some(TEST_ALLOWED_CALLER),
+            invariant_rounding_dust: TEST_INVARIANT_ROUNDING_DUST,
+            balanced_deposits_only: true,
+            owner: Some(TEST_OWNER),
+        });
+
         let mut packed = [0u8; SwapVersion::LATEST_LEN];
         SwapVersion::pack(swap_info, &mut packed).unwrap();
+        assert_eq!(packed[0], 2);
         let unpacked = SwapVersion::unpack(&packed).unwrap();
 
         assert!(unpacked.is_initialized());
@@ -366,6 +1735,205 @@ mod tests {
         assert_eq!(*unpacked.pool_fee_account(), TEST_POOL_FEE_ACCOUNT);
         assert_eq!(*unpacked.fees(), TEST_FEES);
         assert_eq!(*unpacked.swap_curve(), swap_curve);
+        assert_eq!(unpacked.fee_mode(), FeeMode::RetainInPool);
+        assert_eq!(unpacked.fee_discount(), Some(&test_fee_discount()));
+        assert_eq!(unpacked.host_fee_split(), Some(&TEST_HOST_FEE_SPLIT));
+        assert_eq!(
+            unpacked.withdraw_cooldown_secs(),
+            TEST_WITHDRAW_COOLDOWN_SECS
+        );
+        assert_eq!(
+            unpacked.recorded_reserves(),
+            Some((TEST_TOKEN_A_RESERVE, TEST_TOKEN_B_RESERVE))
+        );
+        assert!(!unpacked.host_fees_enabled());
+        assert_eq!(unpacked.max_owner_fee_bps(), TEST_MAX_OWNER_FEE_BPS);
+        assert_eq!(unpacked.min_trade_amount(), Some(TEST_MIN_TRADE_AMOUNT));
+        assert_eq!(unpacked.fee_sweep(), Some(&TEST_FEE_SWEEP));
+        assert_eq!(
+            unpacked.accounted_pool_supply(),
+            Some(TEST_TOTAL_LP_MINTED - TEST_TOTAL_LP_BURNED)
+        );
+        assert_eq!(
+            unpacked.min_reserve_for_swap(),
+            Some(TEST_MIN_RESERVE_FOR_SWAP)
+        );
+        assert!(unpacked.uses_token_2022());
+        assert_eq!(unpacked.host_fee_base(), HostFeeBase::TradeFee);
+        assert_eq!(unpacked.directional_fees(), Some(&TEST_DIRECTIONAL_FEES));
+        assert_eq!(unpacked.launch_fee(), Some(&TEST_LAUNCH_FEE));
+        assert_eq!(unpacked.allowed_caller(), Some(&TEST_ALLOWED_CALLER));
+        assert!(unpacked.balanced_deposits_only());
+        assert_eq!(unpacked.owner(), Some(&TEST_OWNER));
+    }
+
+    #[test]
+    fn swap_v2_pack() {
+        let curve_type = TEST_CURVE_TYPE.try_into().unwrap();
+        let calculator = Arc::new(TEST_CURVE);
+        let swap_curve = SwapCurve {
+            curve_type,
+            calculator,
+        };
+        let swap_info = SwapV2 {
+            is_initialized: true,
+            bump_seed: TEST_BUMP_SEED,
+            token_program_id: TEST_TOKEN_PROGRAM_ID,
+            token_a: TEST_TOKEN_A,
+            token_b: TEST_TOKEN_B,
+            pool_mint: TEST_POOL_MINT,
+            token_a_mint: TEST_TOKEN_A_MINT,
+            token_b_mint: TEST_TOKEN_B_MINT,
+            pool_fee_account: TEST_POOL_FEE_ACCOUNT,
+            fees: TEST_FEES,
+            swap_curve,
+            fee_mode: FeeMode::RetainInPool,
+            fee_discount: test_fee_discount(),
+            host_fee_split: TEST_HOST_FEE_SPLIT,
+            withdraw_cooldown_secs: TEST_WITHDRAW_COOLDOWN_SECS,
+            token_a_reserve: TEST_TOKEN_A_RESERVE,
+            token_b_reserve: TEST_TOKEN_B_RESERVE,
+            host_fees_enabled: false,
+            max_owner_fee_bps: TEST_MAX_OWNER_FEE_BPS,
+            min_trade_amount: Some(TEST_MIN_TRADE_AMOUNT),
+            cumulative_trade_fees_a: TEST_CUMULATIVE_TRADE_FEES_A,
+            cumulative_trade_fees_b: TEST_CUMULATIVE_TRADE_FEES_B,
+            cumulative_owner_fees: TEST_CUMULATIVE_OWNER_FEES,
+            fee_sweep: TEST_FEE_SWEEP,
+            total_lp_minted: TEST_TOTAL_LP_MINTED,
+            total_lp_burned: TEST_TOTAL_LP_BURNED,
+            min_reserve_for_swap: Some(TEST_MIN_RESERVE_FOR_SWAP),
+            uses_token_2022: true,
+            host_fee_base: HostFeeBase::TradeFee,
+            directional_fees: TEST_DIRECTIONAL_FEES,
+            created_at_slot: TEST_CREATED_AT_SLOT,
+            created_at_timestamp: TEST_CREATED_AT_TIMESTAMP,
+            launch_fee: TEST_LAUNCH_FEE,
+            allowed_caller: Some(TEST_ALLOWED_CALLER),
+            invariant_rounding_dust: TEST_INVARIANT_ROUNDING_DUST,
+            balanced_deposits_only: true,
+            owner: Some(TEST_OWNER),
+        };
+
+        let mut packed = [0u8; SwapV2::LEN];
+        SwapV2::pack_into_slice(&swap_info, &mut packed);
+        let unpacked = SwapV2::unpack(&packed).unwrap();
+        assert_eq!(swap_info, unpacked);
+
+        // the v2 layout extends the v1 layout
+        let mut packed_v1 = [0u8; SwapV1::LEN];
+        packed_v1.copy_from_slice(&packed[..SwapV1::LEN]);
+        let unpacked_v1 = SwapV1::unpack(&packed_v1).unwrap();
+        assert_eq!(unpacked_v1.pool_mint, TEST_POOL_MINT);
+        assert_eq!(packed[SwapV1::LEN], FeeMode::RetainInPool as u8);
+
+        // unknown fee mode
+        packed[SwapV1::LEN] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[SwapV1::LEN] = FeeMode::RetainInPool as u8;
+
+        // unknown minimum trade amount tag, ahead of the three fee counters,
+        // the fee sweep, the two supply counters, the minimum reserve, the
+        // token-2022 flag, the host fee base, the directional fees, the
+        // creation slot and timestamp, the launch fee, the allowed caller, the
+        // rounding dust, the balanced deposits flag and the owner
+        let min_trade_amount_offset = SwapV2::LEN
+            - 33
+            - 1
+            - 16
+            - 33
+            - LaunchFee::LEN
+            - 16
+            - DirectionalFees::LEN
+            - 2
+            - 9
+            - 16
+            - FeeSweep::LEN
+            - 24
+            - 9;
+        packed[min_trade_amount_offset] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[min_trade_amount_offset] = 1;
+
+        // unknown minimum reserve tag, ahead of the token-2022 flag, the host
+        // fee base, the directional fees, the creation slot and timestamp, the
+        // launch fee, the allowed caller, the rounding dust, the balanced
+        // deposits flag and the owner
+        let min_reserve_offset =
+            SwapV2::LEN - 33 - 1 - 16 - 33 - LaunchFee::LEN - 16 - DirectionalFees::LEN - 2 - 9;
+        packed[min_reserve_offset] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[min_reserve_offset] = 1;
+
+        // invalid token-2022 flag
+        let uses_token_2022_offset =
+            SwapV2::LEN - 33 - 1 - 16 - 33 - LaunchFee::LEN - 16 - DirectionalFees::LEN - 2;
+        packed[uses_token_2022_offset] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[uses_token_2022_offset] = 1;
+
+        // unknown host fee base
+        let host_fee_base_offset =
+            SwapV2::LEN - 33 - 1 - 16 - 33 - LaunchFee::LEN - 16 - DirectionalFees::LEN - 1;
+        packed[host_fee_base_offset] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[host_fee_base_offset] = HostFeeBase::TradeFee as u8;
+
+        // unknown tag of the B to A fees, ahead of the creation slot and
+        // timestamp, the launch fee, the allowed caller, the rounding dust, the
+        // balanced deposits flag and the owner
+        packed[SwapV2::LEN - 33 - 1 - 16 - 33 - LaunchFee::LEN - 16 - 33] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[SwapV2::LEN - 33 - 1 - 16 - 33 - LaunchFee::LEN - 16 - 33] = 1;
+
+        // unknown allowed caller tag, ahead of the rounding dust, the balanced
+        // deposits flag and the owner
+        packed[SwapV2::LEN - 33 - 1 - 16 - 33] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[SwapV2::LEN - 33 - 1 - 16 - 33] = 1;
+
+        // invalid balanced deposits flag, ahead of the owner
+        packed[SwapV2::LEN - 33 - 1] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        packed[SwapV2::LEN - 33 - 1] = 1;
+
+        // unknown owner tag
+        packed[SwapV2::LEN - 33] = 2;
+        assert_eq!(
+            SwapV2::unpack(&packed).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+
+        let packed = [0u8; SwapV2::LEN];
+        let swap_info: SwapV2 = Default::default();
+        let unpack_unchecked = SwapV2::unpack_unchecked(&packed).unwrap();
+        assert_eq!(unpack_unchecked, swap_info);
+        let err = SwapV2::unpack(&packed).unwrap_err();
+        assert_eq!(err, ProgramError::UninitializedAccount);
     }
 
     #[test]
@@ -424,4 +1992,183 @@ mod tests {
         let err = SwapV1::unpack(&packed).unwrap_err();
         assert_eq!(err, ProgramError::UninitializedAccount);
     }
+
+    #[test]
+    fn fee_discount_tiers() {
+        let fee_discount = test_fee_discount();
+        assert!(fee_discount.is_enabled());
+        assert_eq!(fee_discount.discount_bps(0), 0);
+        assert_eq!(fee_discount.discount_bps(999), 0);
+        assert_eq!(fee_discount.discount_bps(1_000), 2_500);
+        assert_eq!(fee_discount.discount_bps(9_999), 2_500);
+        assert_eq!(fee_discount.discount_bps(10_000), 5_000);
+        assert_eq!(fee_discount.discount_bps(u64::MAX), 5_000);
+        assert_eq!(fee_discount.validate(), Ok(()));
+
+        let mut packed = [0u8; FeeDiscount::LEN];
+        fee_discount.pack_into_slice(&mut packed);
+        assert_eq!(
+            FeeDiscount::unpack_from_slice(&packed).unwrap(),
+            fee_discount
+        );
+
+        let mut invalid = fee_discount;
+        invalid.tiers[2] = FeeDiscountTier {
+            threshold: 100_000,
+            discount_bps: 10_001,
+        };
+        assert_eq!(invalid.validate(), Err(SwapError::InvalidFee));
+
+        assert!(!FeeDiscount::default().is_enabled());
+    }
+
+    #[test]
+    fn host_fee_split_shares() {
+        let host_fee_split = TEST_HOST_FEE_SPLIT;
+        assert!(host_fee_split.is_enabled());
+        assert_eq!(host_fee_split.recipients(), 2);
+        assert_eq!(host_fee_split.validate(), Ok(()));
+        assert_eq!(host_fee_split.shares(400), Some([100, 300, 0, 0]));
+        // the rounding remainder goes to the first recipient
+        assert_eq!(host_fee_split.shares(7), Some([2, 5, 0, 0]));
+        for host_fee in 0..100 {
+            let shares = host_fee_split.shares(host_fee).unwrap();
+            assert_eq!(shares.iter().sum::<u128>(), host_fee);
+        }
+
+        let mut packed = [0u8; HostFeeSplit::LEN];
+        host_fee_split.pack_into_slice(&mut packed);
+        assert_eq!(packed, [1, 0, 3, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            HostFeeSplit::unpack_from_slice(&packed).unwrap(),
+            host_fee_split
+        );
+
+        let invalid = HostFeeSplit {
+            weights: [1, 0, 3, 0],
+        };
+        assert_eq!(invalid.validate(), Err(SwapError::InvalidFee));
+
+        let disabled = HostFeeSplit::default();
+        assert!(!disabled.is_enabled());
+        assert_eq!(disabled.shares(7), Some([7, 0, 0, 0]));
+    }
+
+    #[test]
+    fn fee_sweep_split() {
+        let fee_sweep = TEST_FEE_SWEEP;
+        assert!(fee_sweep.is_enabled());
+        assert_eq!(fee_sweep.validate(), Ok(()));
+        assert_eq!(fee_sweep.split(0, 1_000), (1_000, 0));
+        assert_eq!(fee_sweep.split(4_500, 1_000), (500, 500));
+        assert_eq!(fee_sweep.split(5_000, 1_000), (0, 1_000));
+        assert_eq!(fee_sweep.split(9_000, 1_000), (0, 1_000));
+
+        let mut packed = [0u8; FeeSweep::LEN];
+        fee_sweep.pack_into_slice(&mut packed);
+        assert_eq!(packed[..8], 5_000u64.to_le_bytes());
+        assert_eq!(FeeSweep::unpack_from_slice(&packed).unwrap(), fee_sweep);
+
+        let invalid = FeeSweep {
+            threshold: 5_000,
+            destination: Pubkey::default(),
+        };
+        assert_eq!(invalid.validate(), Err(SwapError::InvalidFeeAccount));
+
+        let disabled = FeeSweep::default();
+        assert!(!disabled.is_enabled());
+        assert_eq!(disabled.validate(), Ok(()));
+    }
+
+    #[test]
+    fn directional_fees_per_direction() {
+        let directional_fees = TEST_DIRECTIONAL_FEES;
+        assert!(directional_fees.is_enabled());
+        assert_eq!(
+            directional_fees.fees(&TEST_FEES, TradeDirection::AtoB),
+            TEST_FEES
+        );
+        assert_eq!(
+            directional_fees.fees(&TEST_FEES, TradeDirection::BtoA),
+            Fees {
+                trade_fee_numerator: 30,
+                trade_fee_denominator: 10_000,
+                owner_trade_fee_numerator: 10,
+                owner_trade_fee_denominator: 10_000,
+                ..TEST_FEES
+            }
+        );
+        assert_eq!(directional_fees.validate(&TEST_FEES), Ok(()));
+
+        let mut packed = [0u8; DirectionalFees::LEN];
+        directional_fees.pack_into_slice(&mut packed);
+        assert_eq!(packed[..33], [0u8; 33]);
+        assert_eq!(packed[33], 1);
+        assert_eq!(
+            DirectionalFees::unpack_from_slice(&packed).unwrap(),
+            directional_fees
+        );
+
+        // a zero denominator with a fee is rejected
+        let invalid = DirectionalFees {
+            a_to_b: Some(TradeFees {
+                trade_fee_numerator: 1,
+                ..TradeFees::default()
+            }),
+            b_to_a: None,
+        };
+        assert_eq!(invalid.validate(&TEST_FEES), Err(SwapError::InvalidFee));
+
+        assert!(!DirectionalFees::default().is_enabled());
+    }
+
+    #[test]
+    fn launch_fee_decay() {
+        let launch_fee = TEST_LAUNCH_FEE;
+        let launch_time = TEST_CREATED_AT_TIMESTAMP;
+        assert!(launch_fee.is_enabled());
+        assert_eq!(launch_fee.validate(), Ok(()));
+
+        // 上线前和上线时按上线费率, 之后线性衰减到底线
+        assert_eq!(launch_fee.fee_bps(launch_time - 1), 500);
+        assert_eq!(launch_fee.fee_bps(launch_time), 500);
+        assert_eq!(launch_fee.fee_bps(launch_time + 21_600), 383);
+        assert_eq!(launch_fee.fee_bps(launch_time + 43_200), 265);
+        assert_eq!(launch_fee.fee_bps(launch_time + 86_399), 31);
+        assert_eq!(launch_fee.fee_bps(launch_time + 86_400), 30);
+        assert_eq!(launch_fee.fee_bps(i64::MAX), 30);
+        assert_eq!(
+            launch_fee.fees(&TEST_FEES, launch_time + 43_200),
+            Fees {
+                trade_fee_numerator: 265,
+                trade_fee_denominator: 10_000,
+                ..TEST_FEES
+            }
+        );
+
+        let mut packed = [0u8; LaunchFee::LEN];
+        launch_fee.pack_into_slice(&mut packed);
+        assert_eq!(packed[..2], 500u16.to_le_bytes());
+        assert_eq!(LaunchFee::unpack_from_slice(&packed).unwrap(), launch_fee);
+
+        for invalid in [
+            LaunchFee {
+                launch_fee_bps: 10_001,
+                ..launch_fee
+            },
+            LaunchFee {
+                floor_fee_bps: 501,
+                ..launch_fee
+            },
+            LaunchFee {
+                decay_secs: 0,
+                ..launch_fee
+            },
+        ] {
+            assert_eq!(invalid.validate(), Err(SwapError::InvalidFee));
+        }
+
+        assert!(!LaunchFee::default().is_enabled());
+        assert_eq!(LaunchFee::default().validate(), Ok(()));
+    }
 }